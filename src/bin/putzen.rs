@@ -1,24 +1,41 @@
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::ffi::OsStr;
 use std::io::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 use argh::FromArgs;
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use jwalk::Parallelism;
 
 use putzen_cli::caches;
+use putzen_cli::check::CheckReport;
+use putzen_cli::config::{
+    self, CliValues, Settings, Source, DEFAULT_HIDDEN_GLOB, DEFAULT_PARALLELISM,
+};
+use putzen_cli::edit_plan::{self, PlanCandidate};
+use putzen_cli::list::ListSort;
+use putzen_cli::mounts::MountFilter;
+use putzen_cli::ownership::{ForeignFolder, OwnerFilter};
+use putzen_cli::plan_file::{PlanFile, PlanRecorder, PlannedFolder};
+use putzen_cli::project_config::{self, ProjectConfig, ProjectScope};
+use putzen_cli::reclaim::{FreeSpace, Goal, Rank, Reclaim, Statvfs};
+use putzen_cli::report::{Event, EventKind, OutputFormat, Reporter, RunSummary};
+use putzen_cli::summary::{Recording, RunTable};
+use putzen_cli::watch::Watch;
 use putzen_cli::{
-    DecisionContext, DoCleanUp, DryRunCleaner, FileToFolderMatch, Folder, FolderProcessed,
-    HumanReadable, IsFolderToRemove, NiceInteractiveDecider, NoOpObserver, ProperCleaner,
-    RunObserver,
+    Decide, DecisionContext, DoCleanUp, DryRunCleaner, FileToFolderMatch, Folder, FolderProcessed,
+    HumanReadable, IsFolderToRemove, NiceInteractiveDecider, NoOpObserver, PlannedDecider,
+    ProperCleaner, RunObserver,
 };
 
 #[cfg(feature = "highscore-board")]
 use putzen_cli::HighscoreObserver;
 
-/// Static glob pattern used when neither `-a` nor `--hidden` is given.
-const DEFAULT_HIDDEN_GLOB: &str = ".worktrees";
 /// Static glob pattern used for `-a` / `--dive-into-hidden-folders`.
 const ALL_HIDDEN_GLOB: &str = "*";
 
@@ -68,9 +85,17 @@ impl HiddenPolicy {
     /// rules and applies the default `.worktrees` pattern when no
     /// `--hidden` and no `-a` was passed.
     fn from_args(args: &PutzenCliArgs) -> std::result::Result<Self, String> {
-        let dash_a = args.dive_into_hidden_folders;
-        let no_hidden = args.no_hidden;
-        let hidden_given = !args.hidden.is_empty();
+        Self::from_flags(args.dive_into_hidden_folders, args.no_hidden, &args.hidden)
+    }
+
+    /// Shared by every command that walks for artifacts: `-a`,
+    /// `--no-hidden` and `--hidden` as parsed by argh.
+    fn from_flags(
+        dash_a: bool,
+        no_hidden: bool,
+        hidden: &[Glob],
+    ) -> std::result::Result<Self, String> {
+        let hidden_given = !hidden.is_empty();
 
         if no_hidden && hidden_given {
             return Err("`--no-hidden` and `--hidden` are mutually exclusive".into());
@@ -93,7 +118,7 @@ impl HiddenPolicy {
         let owned: Vec<Glob> = if dash_a {
             vec![parse_glob(ALL_HIDDEN_GLOB).expect("static glob must parse")]
         } else if hidden_given {
-            for g in &args.hidden {
+            for g in hidden {
                 let pat = g.glob();
                 if !pattern_can_match_hidden(pat) {
                     eprintln!(
@@ -102,7 +127,7 @@ impl HiddenPolicy {
                     );
                 }
             }
-            args.hidden.clone()
+            hidden.to_vec()
         } else {
             vec![parse_glob(DEFAULT_HIDDEN_GLOB).expect("static glob must parse")]
         };
@@ -113,9 +138,9 @@ impl HiddenPolicy {
 
 /// all supported this to clean up
 static FOLDER_TO_CLEANUP: [FileToFolderMatch; 3] = [
-    FileToFolderMatch::new("Cargo.toml", "target"),
-    FileToFolderMatch::new("package.json", "node_modules"),
-    FileToFolderMatch::new("CMakeLists.txt", "build"),
+    FileToFolderMatch::new("Cargo.toml", "target").with_ecosystem("rust"),
+    FileToFolderMatch::new("package.json", "node_modules").with_ecosystem("javascript"),
+    FileToFolderMatch::new("CMakeLists.txt", "build").with_ecosystem("cmake"),
 ];
 
 #[derive(FromArgs)]
@@ -146,6 +171,10 @@ struct PutzenCliArgs {
     #[argh(switch, short = 'd')]
     dry_run: bool,
 
+    /// delete for real even if the config or PUTZEN_DRY_RUN asks for a dry run
+    #[argh(switch)]
+    no_dry_run: bool,
+
     /// switch to say yes to all questions
     #[argh(switch, short = 'y')]
     yes_to_all: bool,
@@ -154,6 +183,59 @@ struct PutzenCliArgs {
     #[argh(switch, short = 'L')]
     follow: bool,
 
+    /// number of threads walking the tree (default: 8)
+    #[argh(option)]
+    parallelism: Option<usize>,
+
+    /// only offer folders whose newest file is older than this, e.g. `30d`.
+    /// A project's `.putzen.toml` can override it for its subtree
+    #[argh(option, from_str_fn(caches::parse_duration))]
+    older_than: Option<Duration>,
+
+    /// clean the highest ranked folders (size × age) until the filesystem
+    /// has this much free space, e.g. `50G`
+    #[argh(option, from_str_fn(caches::parse_size))]
+    until_free: Option<u64>,
+
+    /// clean the highest ranked folders (size × age) until at least this
+    /// much space was freed, e.g. `20G`
+    #[argh(option, from_str_fn(caches::parse_size))]
+    free_at_least: Option<u64>,
+
+    /// browse the found artifacts in a full-screen TUI instead of
+    /// answering one prompt per folder
+    #[argh(switch)]
+    tui: bool,
+
+    /// review the whole deletion plan in $EDITOR instead of answering one
+    /// prompt per folder
+    #[argh(switch)]
+    edit: bool,
+
+    /// output format: `text` (default), `json` for one report at the end,
+    /// or `ndjson` for one event per line as the run happens
+    #[argh(option, default = "OutputFormat::Text")]
+    format: OutputFormat,
+
+    /// clean folders no matter who owns them; by default folders of other
+    /// users are skipped
+    #[argh(switch)]
+    any_owner: bool,
+
+    /// clean the folders of this user (name or uid) instead of your own
+    #[argh(option)]
+    owner: Option<String>,
+
+    /// walk into mounts of this filesystem type (repeatable), e.g. `nfs` or
+    /// `sshfs`. Network and FUSE filesystems are skipped by default
+    #[argh(option)]
+    include_fs: Vec<String>,
+
+    /// with `--dry-run`: write the folders that would be deleted, with their
+    /// size, newest mtime and rule, to this file for `putzen apply`
+    #[argh(option)]
+    plan_out: Option<PathBuf>,
+
     /// include every hidden directory (== --hidden '*')
     #[argh(switch, short = 'a')]
     dive_into_hidden_folders: bool,
@@ -173,21 +255,345 @@ struct PutzenCliArgs {
     folder: PathBuf,
 }
 
+#[derive(FromArgs)]
+/// list artifact folders with their size and age, without deleting anything
+///
+/// Exits with 0 when at least one folder of `--min-size` or more was found,
+/// and with 1 otherwise.
+///
+/// Examples:
+///     putzen list ~/src                   # largest first
+///     putzen list --sort age --top 20     # the 20 oldest
+///     putzen list --min-size 1G || echo "all clean"
+struct ListCliArgs {
+    /// sort by `size` (default, largest first), `age` (oldest first) or `path`
+    #[argh(option, default = "ListSort::Size")]
+    sort: ListSort,
+
+    /// only show the first N folders after sorting
+    #[argh(option)]
+    top: Option<usize>,
+
+    /// only list folders of at least this size, e.g. `500M` or `1G`
+    #[argh(option, from_str_fn(caches::parse_size), default = "0")]
+    min_size: u64,
+
+    /// follow symbolic links
+    #[argh(switch, short = 'L')]
+    follow: bool,
+
+    /// number of threads walking the tree (default: 8)
+    #[argh(option)]
+    parallelism: Option<usize>,
+
+    /// walk into mounts of this filesystem type (repeatable), e.g. `nfs` or
+    /// `sshfs`. Network and FUSE filesystems are skipped by default
+    #[argh(option)]
+    include_fs: Vec<String>,
+
+    /// include every hidden directory (== --hidden '*')
+    #[argh(switch, short = 'a')]
+    dive_into_hidden_folders: bool,
+
+    /// skip every hidden directory (overrides the default `.worktrees`)
+    #[argh(switch)]
+    no_hidden: bool,
+
+    /// glob of hidden directories to descend into (repeatable), see
+    /// `putzen --help`. Default: `.worktrees`.
+    #[argh(option, from_str_fn(parse_glob))]
+    hidden: Vec<Glob>,
+
+    /// path where to start looking for artifacts.
+    #[argh(positional, default = "PathBuf::from(\".\")")]
+    folder: PathBuf,
+}
+
+#[derive(FromArgs)]
+/// stay resident and clean artifacts whenever free disk space runs low.
+///
+/// Every interval the free space of each root's filesystem is checked. Below
+/// the threshold, artifacts under that root are cleaned without asking,
+/// highest ranked first, until the goal is met. Every step is appended to
+/// the deletion journal. SIGTERM or Ctrl-C stops after the current folder.
+///
+/// Examples:
+///     putzen watch --below 20G ~/src
+///     putzen watch --below 20G --until 50G --rank age --interval 10m ~/src /ci
+struct WatchCliArgs {
+    /// clean when a root's filesystem has less than this free, e.g. `20G`
+    #[argh(option, from_str_fn(caches::parse_size))]
+    below: u64,
+
+    /// clean until this much is free again (default: the `--below` value)
+    #[argh(option, from_str_fn(caches::parse_size))]
+    until: Option<u64>,
+
+    /// how often to check the free space, e.g. `30s` or `5m` (default: 5m)
+    #[argh(
+        option,
+        from_str_fn(caches::parse_duration),
+        default = "Duration::from_secs(300)"
+    )]
+    interval: Duration,
+
+    /// what goes first: `score` (size × age, default), `age` or `size`
+    #[argh(option, default = "Rank::Score")]
+    rank: Rank,
+
+    /// only clean folders whose newest file is older than this, e.g. `30d`
+    #[argh(option, from_str_fn(caches::parse_duration))]
+    older_than: Option<Duration>,
+
+    /// dry run: log what would be cleaned without deleting anything
+    #[argh(switch, short = 'd')]
+    dry_run: bool,
+
+    /// delete for real even if the config or PUTZEN_DRY_RUN asks for a dry run
+    #[argh(switch)]
+    no_dry_run: bool,
+
+    /// clean folders no matter who owns them; by default folders of other
+    /// users are skipped
+    #[argh(switch)]
+    any_owner: bool,
+
+    /// clean the folders of this user (name or uid) instead of your own
+    #[argh(option)]
+    owner: Option<String>,
+
+    /// append to this journal instead of $PUTZEN_JOURNAL or
+    /// ~/.local/share/putzen/journal.ndjson
+    #[argh(option)]
+    journal: Option<PathBuf>,
+
+    /// follow symbolic links
+    #[argh(switch, short = 'L')]
+    follow: bool,
+
+    /// number of threads walking the tree (default: 8)
+    #[argh(option)]
+    parallelism: Option<usize>,
+
+    /// walk into mounts of this filesystem type (repeatable), e.g. `nfs` or
+    /// `sshfs`. Network and FUSE filesystems are skipped by default
+    #[argh(option)]
+    include_fs: Vec<String>,
+
+    /// include every hidden directory (== --hidden '*')
+    #[argh(switch, short = 'a')]
+    dive_into_hidden_folders: bool,
+
+    /// skip every hidden directory (overrides the default `.worktrees`)
+    #[argh(switch)]
+    no_hidden: bool,
+
+    /// glob of hidden directories to descend into (repeatable), see
+    /// `putzen --help`. Default: `.worktrees`.
+    #[argh(option, from_str_fn(parse_glob))]
+    hidden: Vec<Glob>,
+
+    /// folders to watch and clean (default: the current folder)
+    #[argh(positional)]
+    roots: Vec<PathBuf>,
+}
+
+#[derive(FromArgs)]
+/// write reclaimable space as Prometheus gauges for node_exporter's
+/// textfile collector. Never deletes anything.
+///
+/// Gauges cover artifact bytes per ecosystem, cache bytes per cache, the
+/// oldest artifact age and the counters of the last run in the deletion
+/// journal.
+///
+/// Examples:
+///     putzen metrics --out /var/lib/node_exporter/putzen.prom ~/src
+///     putzen metrics ~/src            # print to stdout
+struct MetricsCliArgs {
+    /// write to this file (atomically) instead of stdout
+    #[argh(option)]
+    out: Option<PathBuf>,
+
+    /// cache directory to scan instead of the built-in defaults (repeatable)
+    #[argh(option)]
+    cache_root: Vec<PathBuf>,
+
+    /// read the last run from this journal instead of $PUTZEN_JOURNAL or
+    /// ~/.local/share/putzen/journal.ndjson
+    #[argh(option)]
+    journal: Option<PathBuf>,
+
+    /// follow symbolic links
+    #[argh(switch, short = 'L')]
+    follow: bool,
+
+    /// number of threads walking the tree (default: 8)
+    #[argh(option)]
+    parallelism: Option<usize>,
+
+    /// walk into mounts of this filesystem type (repeatable), e.g. `nfs` or
+    /// `sshfs`. Network and FUSE filesystems are skipped by default
+    #[argh(option)]
+    include_fs: Vec<String>,
+
+    /// include every hidden directory (== --hidden '*')
+    #[argh(switch, short = 'a')]
+    dive_into_hidden_folders: bool,
+
+    /// skip every hidden directory (overrides the default `.worktrees`)
+    #[argh(switch)]
+    no_hidden: bool,
+
+    /// glob of hidden directories to descend into (repeatable), see
+    /// `putzen --help`. Default: `.worktrees`.
+    #[argh(option, from_str_fn(parse_glob))]
+    hidden: Vec<Glob>,
+
+    /// folders to look for artifacts in (default: the current folder)
+    #[argh(positional)]
+    roots: Vec<PathBuf>,
+}
+
+#[derive(FromArgs)]
+/// delete exactly the folders of a plan written by `putzen -d --plan-out`.
+///
+/// Every folder is checked against the size, newest mtime and rule recorded
+/// in the plan first. If any of them changed, nothing is deleted.
+///
+/// Examples:
+///     putzen -d --plan-out plan.json ~/src    # alice makes a plan
+///     putzen apply plan.json                  # bob reviewed and applies it
+struct ApplyCliArgs {
+    /// only check the plan and show what would be deleted
+    #[argh(switch, short = 'd')]
+    dry_run: bool,
+
+    /// delete the unchanged folders and only warn about the drifted ones
+    #[argh(switch)]
+    skip_drifted: bool,
+
+    /// delete planned folders no matter who owns them; by default folders
+    /// of other users are skipped
+    #[argh(switch)]
+    any_owner: bool,
+
+    /// delete the planned folders of this user (name or uid) instead of
+    /// your own
+    #[argh(option)]
+    owner: Option<String>,
+
+    /// the plan file
+    #[argh(positional)]
+    plan: PathBuf,
+}
+
+#[derive(FromArgs)]
+/// fail when the artifacts under a folder exceed a size budget, e.g. as a
+/// CI step. Never deletes anything.
+///
+/// Exits with 0 within budget and 1 over budget, listing the largest
+/// folders.
+///
+/// Examples:
+///     putzen check --max-reclaimable 20G $CI_WORKSPACE
+struct CheckCliArgs {
+    /// the budget, e.g. `20G`
+    #[argh(option, from_str_fn(caches::parse_size))]
+    max_reclaimable: u64,
+
+    /// how many of the largest folders to list when over budget (default: 5)
+    #[argh(option, default = "5")]
+    top: usize,
+
+    /// follow symbolic links
+    #[argh(switch, short = 'L')]
+    follow: bool,
+
+    /// number of threads walking the tree (default: 8)
+    #[argh(option)]
+    parallelism: Option<usize>,
+
+    /// walk into mounts of this filesystem type (repeatable), e.g. `nfs` or
+    /// `sshfs`. Network and FUSE filesystems are skipped by default
+    #[argh(option)]
+    include_fs: Vec<String>,
+
+    /// include every hidden directory (== --hidden '*')
+    #[argh(switch, short = 'a')]
+    dive_into_hidden_folders: bool,
+
+    /// skip every hidden directory (overrides the default `.worktrees`)
+    #[argh(switch)]
+    no_hidden: bool,
+
+    /// glob of hidden directories to descend into (repeatable), see
+    /// `putzen --help`. Default: `.worktrees`.
+    #[argh(option, from_str_fn(parse_glob))]
+    hidden: Vec<Glob>,
+
+    /// path where to start looking for artifacts.
+    #[argh(positional, default = "PathBuf::from(\".\")")]
+    folder: PathBuf,
+}
+
+#[derive(FromArgs)]
+/// inspect the configuration from ~/.config/putzen/config.toml and the
+/// PUTZEN_* environment variables
+struct ConfigCliArgs {
+    #[argh(subcommand)]
+    command: ConfigCommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum ConfigCommand {
+    Show(ConfigShowArgs),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "show")]
+/// print the effective config and where each value comes from
+struct ConfigShowArgs {}
+
 #[derive(FromArgs)]
 /// interactive cleanup of user-level cache directories
 struct CachesCliArgs {
     /// scan root (repeatable). When given, REPLACES the built-in defaults.
     #[argh(option)]
     root: Vec<PathBuf>,
+    /// scan root on top of the defaults (repeatable)
+    #[argh(option)]
+    add_root: Vec<PathBuf>,
+    /// leave out the default cache with this label (repeatable)
+    #[argh(option)]
+    disable: Vec<String>,
     /// caches whose newest file is younger than this are flagged ACTIVE
     #[argh(option)]
     floor: Option<String>,
     /// dry run: never delete, just show what would happen
     #[argh(switch)]
     dry_run: bool,
+    /// delete for real even if the config or PUTZEN_DRY_RUN asks for a dry run
+    #[argh(switch)]
+    no_dry_run: bool,
     /// skip the deletion confirmation modal
     #[argh(switch, short = 'y')]
     yes: bool,
+    /// print the ranked caches as a table instead of opening the TUI
+    #[argh(switch)]
+    list: bool,
+    /// print --list or --delete-older-than results as JSON
+    #[argh(switch)]
+    json: bool,
+    /// delete caches not used for this long (e.g. 90d) without the TUI
+    #[argh(option, from_str_fn(caches::parse_duration))]
+    delete_older_than: Option<std::time::Duration>,
+    /// only list or delete caches of at least this size (e.g. 1G)
+    #[argh(option, from_str_fn(caches::parse_size), default = "0")]
+    min_size: u64,
+    /// project folder whose Cargo.lock files keep cargo registry entries in use (repeatable)
+    #[argh(option)]
+    project_root: Vec<PathBuf>,
 }
 
 fn main() -> Result<()> {
@@ -203,15 +609,157 @@ fn main() -> Result<()> {
                 std::process::exit(if e.status.is_ok() { 0 } else { 1 });
             }
         };
+        let settings = Settings::load(CliValues {
+            caches_floor: parsed.floor,
+            caches_dry_run: cli_dry_run(parsed.dry_run, parsed.no_dry_run),
+            ..Default::default()
+        })?;
         return caches::run(caches::CachesArgs {
-            roots: parsed.root,
-            floor: parsed.floor,
-            dry_run: parsed.dry_run,
+            seeds: caches::SeedSelection {
+                roots: parsed.root,
+                add_roots: parsed.add_root,
+                disable: parsed.disable,
+            },
+            floor: Some(settings.caches_floor.value),
+            dry_run: settings.caches_dry_run.value,
             yes: parsed.yes,
+            list: parsed.list,
+            json: parsed.json,
+            delete_older_than: parsed.delete_older_than,
+            min_size: parsed.min_size,
+            project_roots: parsed.project_root,
         });
     }
 
-    let args: PutzenCliArgs = argh::from_env();
+    if raw.get(1).map(String::as_str) == Some("config") {
+        let rest: Vec<&str> = raw[2..].iter().map(|s| s.as_str()).collect();
+        let parsed = match ConfigCliArgs::from_args(&["putzen config"], &rest) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("{}", e.output);
+                std::process::exit(if e.status.is_ok() { 0 } else { 1 });
+            }
+        };
+        let ConfigCommand::Show(_) = parsed.command;
+        match config::config_path() {
+            Some(path) if path.exists() => println!("# config file: {}\n", path.display()),
+            Some(path) => println!("# config file: {} (not found)\n", path.display()),
+            None => println!("# config file: none, no config directory\n"),
+        }
+        print!("{}", Settings::load(CliValues::default())?.render());
+        return Ok(());
+    }
+
+    if raw.get(1).map(String::as_str) == Some("list") {
+        let rest: Vec<&str> = raw[2..].iter().map(|s| s.as_str()).collect();
+        let mut parsed = match ListCliArgs::from_args(&["putzen list"], &rest) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("{}", e.output);
+                std::process::exit(if e.status.is_ok() { 0 } else { 1 });
+            }
+        };
+        let settings = Settings::load(CliValues {
+            hidden: cli_hidden(
+                parsed.dive_into_hidden_folders,
+                parsed.no_hidden,
+                &parsed.hidden,
+            ),
+            parallelism: parsed.parallelism,
+            ..Default::default()
+        })?;
+        apply_hidden(&settings, &mut parsed.no_hidden, &mut parsed.hidden)?;
+        parsed.parallelism = Some(settings.parallelism.value);
+        let found = list(&parsed)?;
+        std::process::exit(if found { 0 } else { 1 });
+    }
+
+    if raw.get(1).map(String::as_str) == Some("watch") {
+        let rest: Vec<&str> = raw[2..].iter().map(|s| s.as_str()).collect();
+        let mut parsed = match WatchCliArgs::from_args(&["putzen watch"], &rest) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("{}", e.output);
+                std::process::exit(if e.status.is_ok() { 0 } else { 1 });
+            }
+        };
+        let settings = Settings::load(CliValues {
+            hidden: cli_hidden(
+                parsed.dive_into_hidden_folders,
+                parsed.no_hidden,
+                &parsed.hidden,
+            ),
+            dry_run: cli_dry_run(parsed.dry_run, parsed.no_dry_run),
+            parallelism: parsed.parallelism,
+            ..Default::default()
+        })?;
+        apply_hidden(&settings, &mut parsed.no_hidden, &mut parsed.hidden)?;
+        parsed.dry_run = settings.dry_run.value;
+        parsed.parallelism = Some(settings.parallelism.value);
+        return watch(&parsed);
+    }
+
+    if raw.get(1).map(String::as_str) == Some("metrics") {
+        let rest: Vec<&str> = raw[2..].iter().map(|s| s.as_str()).collect();
+        let mut parsed = match MetricsCliArgs::from_args(&["putzen metrics"], &rest) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("{}", e.output);
+                std::process::exit(if e.status.is_ok() { 0 } else { 1 });
+            }
+        };
+        let settings = Settings::load(CliValues {
+            hidden: cli_hidden(
+                parsed.dive_into_hidden_folders,
+                parsed.no_hidden,
+                &parsed.hidden,
+            ),
+            parallelism: parsed.parallelism,
+            ..Default::default()
+        })?;
+        apply_hidden(&settings, &mut parsed.no_hidden, &mut parsed.hidden)?;
+        parsed.parallelism = Some(settings.parallelism.value);
+        return metrics(&parsed);
+    }
+
+    if raw.get(1).map(String::as_str) == Some("check") {
+        let rest: Vec<&str> = raw[2..].iter().map(|s| s.as_str()).collect();
+        let mut parsed = match CheckCliArgs::from_args(&["putzen check"], &rest) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("{}", e.output);
+                std::process::exit(if e.status.is_ok() { 0 } else { 1 });
+            }
+        };
+        let settings = Settings::load(CliValues {
+            hidden: cli_hidden(
+                parsed.dive_into_hidden_folders,
+                parsed.no_hidden,
+                &parsed.hidden,
+            ),
+            parallelism: parsed.parallelism,
+            ..Default::default()
+        })?;
+        apply_hidden(&settings, &mut parsed.no_hidden, &mut parsed.hidden)?;
+        parsed.parallelism = Some(settings.parallelism.value);
+        let within = check(&parsed)?;
+        std::process::exit(if within { 0 } else { 1 });
+    }
+
+    if raw.get(1).map(String::as_str) == Some("apply") {
+        let rest: Vec<&str> = raw[2..].iter().map(|s| s.as_str()).collect();
+        let parsed = match ApplyCliArgs::from_args(&["putzen apply"], &rest) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("{}", e.output);
+                std::process::exit(if e.status.is_ok() { 0 } else { 1 });
+            }
+        };
+        let clean = apply(&parsed)?;
+        std::process::exit(if clean { 0 } else { 1 });
+    }
+
+    let mut args: PutzenCliArgs = argh::from_env();
     if args.version {
         println!("{} {}", env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"));
         return Ok(());
@@ -222,55 +770,131 @@ fn main() -> Result<()> {
         println!("{}", putzen_cli::render_board(&highscores));
         return Ok(());
     }
+    let settings = Settings::load(CliValues {
+        hidden: cli_hidden(args.dive_into_hidden_folders, args.no_hidden, &args.hidden),
+        dry_run: cli_dry_run(args.dry_run, args.no_dry_run),
+        parallelism: args.parallelism,
+        ..Default::default()
+    })?;
+    apply_hidden(&settings, &mut args.no_hidden, &mut args.hidden)?;
+    args.dry_run = settings.dry_run.value;
+    args.parallelism = Some(settings.parallelism.value);
+
+    if args.tui && args.edit {
+        eprintln!("`--tui` and `--edit` are mutually exclusive");
+        std::process::exit(1);
+    }
+    if args.until_free.is_some() && args.free_at_least.is_some() {
+        eprintln!("`--until-free` and `--free-at-least` are mutually exclusive");
+        std::process::exit(1);
+    }
+    if (args.until_free.is_some() || args.free_at_least.is_some()) && (args.tui || args.edit) {
+        eprintln!(
+            "`--until-free` and `--free-at-least` cannot be combined with `--tui` or `--edit`"
+        );
+        std::process::exit(1);
+    }
+    if args.plan_out.is_some() && (!args.dry_run || args.tui) {
+        eprintln!("`--plan-out` needs `--dry-run` and cannot be combined with `--tui`");
+        std::process::exit(1);
+    }
+    if args.tui && args.format.is_machine_readable() {
+        eprintln!("`--tui` cannot be combined with `--format json` or `--format ndjson`");
+        std::process::exit(1);
+    }
+    if args.tui {
+        return visit_path_tui(&args);
+    }
     visit_path(&args)
 }
 
-fn visit_path(args: &PutzenCliArgs) -> Result<()> {
-    let to_clean = &FOLDER_TO_CLEANUP;
-    let mut decider = NiceInteractiveDecider::default();
-    let mut amount_cleaned = 0;
-    let folder = args
-        .folder
-        .canonicalize()
-        .expect("Folder cannot be canonicalized.");
-    let ctx = DecisionContext {
-        working_dir: folder.clone(),
-        is_dry_run: args.dry_run,
-        yes_to_all: args.yes_to_all,
-    };
-
-    let cleaner: Box<dyn DoCleanUp> = if args.dry_run {
-        Box::new(DryRunCleaner)
+/// The hidden-directory flags as the config layer sees them: `None` when
+/// none of `-a`, `--no-hidden` and `--hidden` was given.
+fn cli_hidden(dash_a: bool, no_hidden: bool, hidden: &[Glob]) -> Option<Vec<String>> {
+    if dash_a {
+        Some(vec![ALL_HIDDEN_GLOB.to_string()])
+    } else if no_hidden {
+        Some(Vec::new())
+    } else if !hidden.is_empty() {
+        Some(hidden.iter().map(|g| g.glob().to_string()).collect())
     } else {
-        Box::new(ProperCleaner)
-    };
+        None
+    }
+}
 
-    let mut observer: Box<dyn RunObserver> = if !args.dry_run {
-        #[cfg(feature = "highscore-board")]
-        {
-            Box::new(HighscoreObserver::load()?)
-        }
-        #[cfg(not(feature = "highscore-board"))]
-        {
-            Box::new(NoOpObserver)
-        }
+/// `-d` wins over `--no-dry-run`; with neither, env or config decide.
+fn cli_dry_run(dry_run: bool, no_dry_run: bool) -> Option<bool> {
+    if dry_run {
+        Some(true)
+    } else if no_dry_run {
+        Some(false)
     } else {
-        Box::new(NoOpObserver)
-    };
+        None
+    }
+}
 
-    let hidden_policy = HiddenPolicy::from_args(args)
+/// Without any hidden-directory flag on the command line, take the globs
+/// from env or config. An empty list there means `--no-hidden`.
+fn apply_hidden(settings: &Settings, no_hidden: &mut bool, hidden: &mut Vec<Glob>) -> Result<()> {
+    if settings.hidden.source == Source::Cli {
+        return Ok(());
+    }
+    *hidden = settings
+        .hidden
+        .value
+        .iter()
+        .map(|g| parse_glob(g))
+        .collect::<std::result::Result<_, _>>()
         .map_err(|msg| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg))?;
+    *no_hidden = hidden.is_empty();
+    Ok(())
+}
+
+/// An artifact folder found by the walk, with the rule that matched it.
+type Candidate = (Folder, &'static FileToFolderMatch);
+
+/// Walk `root` and yield every folder that matches one of
+/// `FOLDER_TO_CLEANUP`, or an extra target of a `.putzen.toml`, honouring
+/// `-L`, the hidden-dir policy and `older_than`. Matched folders are not
+/// descended into.
+fn artifact_folders(
+    root: PathBuf,
+    follow: bool,
+    parallelism: usize,
+    older_than: Option<Duration>,
+    hidden_policy: HiddenPolicy,
+    mounts: Arc<MountFilter>,
+) -> impl Iterator<Item = Candidate> {
+    let to_clean = &FOLDER_TO_CLEANUP;
+    let now = SystemTime::now();
 
     // When --no-hidden is set, let jwalk drop hidden entries natively;
     // otherwise we keep them all and let `hidden_policy` decide in the closure.
-    let jwalk_skip_hidden = args.no_hidden;
+    let jwalk_skip_hidden = hidden_policy.no_hidden;
 
-    ctx.println(format!("Start cleaning at {}", folder.display()));
-    for folder in jwalk::WalkDirGeneric::<((), Option<Folder>)>::new(folder)
+    // The read-dir state is the `.putzen.toml` in effect; jwalk hands it
+    // down from each folder to its children.
+    jwalk::WalkDirGeneric::<(Option<Arc<ProjectScope>>, Option<Candidate>)>::new(root)
         .skip_hidden(jwalk_skip_hidden)
-        .follow_links(args.follow)
-        .parallelism(Parallelism::RayonNewPool(8))
-        .process_read_dir(move |depth, _, _, children| {
+        .follow_links(follow)
+        .parallelism(Parallelism::RayonNewPool(parallelism))
+        .process_read_dir(move |depth, path, scope, children| {
+            // 0. a `.putzen.toml` in this folder applies to its whole subtree
+            if depth.is_some() {
+                match ProjectScope::load(path) {
+                    Ok(Some(found)) => *scope = Some(Arc::new(found)),
+                    Ok(None) => {}
+                    Err(msg) => eprintln!(
+                        "warning: ignoring {}: {msg}",
+                        path.join(project_config::FILE_NAME).display()
+                    ),
+                }
+            }
+            if scope.as_ref().is_some_and(|s| s.keep) {
+                children.clear();
+                return;
+            }
+
             // 1. drop hidden children disallowed by the policy.
             // depth=None is the virtual root call (parent of the starting dir);
             // we must NOT filter those children or we'd block the starting dir itself.
@@ -291,7 +915,18 @@ fn visit_path(args: &PutzenCliArgs) -> Result<()> {
                 });
             }
 
-            // 2. keep only directories
+            // 2. stay off network and FUSE mounts, before even a stat on
+            // them; the starting dir itself is always walked
+            if depth.is_some() {
+                children.retain(|dir_entry_result| {
+                    dir_entry_result
+                        .as_ref()
+                        .map(|dir| !mounts.skips(&dir.path()))
+                        .unwrap_or(true)
+                });
+            }
+
+            // 3. keep only directories
             children.retain(|dir_entry_result| {
                 dir_entry_result
                     .as_ref()
@@ -299,17 +934,25 @@ fn visit_path(args: &PutzenCliArgs) -> Result<()> {
                     .unwrap_or(false)
             });
 
-            // 3. existing build-artefact marking
+            // 4. existing build-artefact marking; a project's `older_than`
+            // replaces the one from the command line
+            let older_than = scope.as_ref().and_then(|s| s.older_than).or(older_than);
             children.iter_mut().for_each(|child| {
-                if let Ok(child) = child {
-                    if let Ok(folder) = Folder::try_from(child.path()) {
-                        for rule in to_clean {
-                            if rule.is_folder_to_remove(&folder) {
-                                child.client_state = Some(folder);
-                                child.read_children_path = None;
-                                return;
-                            }
-                        }
+                let Ok(child) = child else {
+                    return;
+                };
+                let Ok(folder) = Folder::try_from(child.path()) else {
+                    return;
+                };
+                let rule = to_clean
+                    .iter()
+                    .find(|rule| rule.is_folder_to_remove(&folder))
+                    .or_else(|| scope.as_ref()?.extra_rule_for(&folder));
+                if let Some(rule) = rule {
+                    child.read_children_path = None;
+                    if older_than.is_none_or(|age| project_config::is_old_enough(&folder, age, now))
+                    {
+                        child.client_state = Some((folder, rule));
                     }
                 }
             });
@@ -317,22 +960,470 @@ fn visit_path(args: &PutzenCliArgs) -> Result<()> {
         .into_iter()
         .filter_map(|f| f.ok())
         .filter_map(|f| f.client_state)
-    {
-        'rules: for rule in to_clean {
-            let result = folder.accept(&ctx, rule, &*cleaner, &mut decider, &mut *observer);
-            match result {
-                Ok(FolderProcessed::Abort) => return Ok(()),
-                Ok(FolderProcessed::Cleaned(size)) => {
-                    amount_cleaned += size;
-                    continue 'rules;
-                }
-                Ok(FolderProcessed::NoRuleMatch) => continue 'rules,
-                Ok(FolderProcessed::Skipped) => continue 'rules,
-                Err(error) => return Err(error),
+}
+
+/// `--tui`: collect every artifact folder up front, then hand them to the
+/// full-screen cleanup TUI shared with `putzen caches`.
+fn visit_path_tui(args: &PutzenCliArgs) -> Result<()> {
+    let folder = args
+        .folder
+        .canonicalize()
+        .expect("Folder cannot be canonicalized.");
+    let hidden_policy = HiddenPolicy::from_args(args)
+        .map_err(|msg| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg))?;
+
+    let owners = owner_filter(args.any_owner, args.owner.as_deref())?;
+
+    let folders = artifact_folders(
+        folder,
+        args.follow,
+        args.parallelism.unwrap_or(DEFAULT_PARALLELISM),
+        args.older_than,
+        hidden_policy,
+        Arc::new(MountFilter::load(&args.include_fs)),
+    )
+    .filter(move |(folder, _)| owners.allows(folder.as_ref()))
+    .collect();
+
+    putzen_cli::projects::run(putzen_cli::projects::ProjectsArgs {
+        folders,
+        dry_run: args.dry_run,
+        yes: args.yes_to_all,
+    })
+}
+
+/// `putzen list`: size every artifact folder and print the table. Returns
+/// whether anything of at least `--min-size` was found.
+fn list(args: &ListCliArgs) -> Result<bool> {
+    let folder = args
+        .folder
+        .canonicalize()
+        .expect("Folder cannot be canonicalized.");
+    let hidden_policy =
+        HiddenPolicy::from_flags(args.dive_into_hidden_folders, args.no_hidden, &args.hidden)
+            .map_err(|msg| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg))?;
+
+    let folders = artifact_folders(
+        folder,
+        args.follow,
+        args.parallelism.unwrap_or(DEFAULT_PARALLELISM),
+        None,
+        hidden_policy,
+        Arc::new(MountFilter::load(&args.include_fs)),
+    )
+    .collect();
+
+    let mut entries = putzen_cli::list::size_all(folders);
+    entries.retain(|e| e.stats.size as u64 >= args.min_size);
+    putzen_cli::list::sort(&mut entries, args.sort);
+    if let Some(top) = args.top {
+        entries.truncate(top);
+    }
+
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    print!(
+        "{}",
+        putzen_cli::list::render(&entries, std::time::SystemTime::now(), home.as_deref())
+    );
+    Ok(!entries.is_empty())
+}
+
+/// `putzen check`: print the verdict, `true` when within budget.
+fn check(args: &CheckCliArgs) -> Result<bool> {
+    let folder = args.folder.canonicalize()?;
+    let hidden_policy =
+        HiddenPolicy::from_flags(args.dive_into_hidden_folders, args.no_hidden, &args.hidden)
+            .map_err(|msg| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg))?;
+    let report = CheckReport::measure(artifact_folders(
+        folder,
+        args.follow,
+        args.parallelism.unwrap_or(DEFAULT_PARALLELISM),
+        None,
+        hidden_policy,
+        Arc::new(MountFilter::load(&args.include_fs)),
+    ));
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    print!(
+        "{}",
+        report.render(args.max_reclaimable, args.top, home.as_deref())
+    );
+    Ok(report.is_within(args.max_reclaimable))
+}
+
+fn metrics(args: &MetricsCliArgs) -> Result<()> {
+    let roots = if args.roots.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        args.roots.clone()
+    };
+    let mounts = Arc::new(MountFilter::load(&args.include_fs));
+    let mut folders = Vec::new();
+    for root in roots {
+        let hidden_policy =
+            HiddenPolicy::from_flags(args.dive_into_hidden_folders, args.no_hidden, &args.hidden)
+                .map_err(|msg| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg))?;
+        folders.extend(artifact_folders(
+            root.canonicalize()?,
+            args.follow,
+            args.parallelism.unwrap_or(DEFAULT_PARALLELISM),
+            None,
+            hidden_policy,
+            Arc::clone(&mounts),
+        ));
+    }
+    let artifacts = putzen_cli::list::size_all(folders);
+
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let seeds = match &home {
+        Some(home) => {
+            let selection = caches::SeedSelection {
+                roots: args.cache_root.clone(),
+                ..Default::default()
             };
+            caches::select_seeds(
+                home,
+                &caches::dropins::load_catalogue()?,
+                &selection,
+                |var| std::env::var(var).ok(),
+            )
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?
         }
+        None => args.cache_root.clone(),
+    };
+    let caches = caches::scan::collect(&seeds);
+
+    // no journal yet just means no run to report on
+    let last_run = args
+        .journal
+        .clone()
+        .or_else(putzen_cli::journal::default_path)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| putzen_cli::metrics::LastRun::from_journal(&text));
+
+    let text =
+        putzen_cli::metrics::render(&artifacts, &caches, last_run.as_ref(), SystemTime::now());
+    match &args.out {
+        Some(path) => putzen_cli::metrics::write_atomically(path, &text),
+        None => {
+            print!("{text}");
+            Ok(())
+        }
+    }
+}
+
+fn watch(args: &WatchCliArgs) -> Result<()> {
+    let roots = if args.roots.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        args.roots.clone()
+    };
+    let roots = roots
+        .iter()
+        .map(|root| root.canonicalize())
+        .collect::<Result<Vec<_>>>()?;
+    // fail on bad hidden or owner flags now, not at the first cleanup
+    HiddenPolicy::from_flags(args.dive_into_hidden_folders, args.no_hidden, &args.hidden)
+        .map_err(|msg| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg))?;
+    owner_filter(args.any_owner, args.owner.as_deref())?;
+    let journal_path = args
+        .journal
+        .clone()
+        .or_else(putzen_cli::journal::default_path)
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no data directory for the journal, pass --journal",
+            )
+        })?;
+    let journal = putzen_cli::journal::open(&journal_path)?;
+    let mut observer = Reporter::new(OutputFormat::Ndjson, journal, Box::new(NoOpObserver));
+
+    let stop = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    for signal in [signal_hook::consts::SIGTERM, signal_hook::consts::SIGINT] {
+        signal_hook::flag::register(signal, Arc::clone(&stop))?;
     }
 
+    let watch = Watch {
+        roots,
+        below: args.below,
+        until: args.until.unwrap_or(args.below),
+        interval: args.interval,
+    };
+    println!(
+        "Watching {} root(s), cleaning below {} free, journal at {}",
+        watch.roots.len(),
+        (watch.below as usize).as_human_readable(),
+        journal_path.display()
+    );
+    let probe: Rc<dyn FreeSpace> = Rc::new(Statvfs);
+    watch.run(probe, &stop, &mut |root, free, reclaim| {
+        if let Err(e) = watch_cleanup(args, root, free, reclaim, &stop, &mut observer) {
+            eprintln!("cleanup of {} failed: {e}", root.display());
+        }
+    });
+    println!("Stopped watching");
+    Ok(())
+}
+
+/// One unattended cleanup of `root` by `putzen watch`: every candidate is a
+/// yes, highest ranked first, until the goal is met or a stop is requested.
+fn watch_cleanup(
+    args: &WatchCliArgs,
+    root: &Path,
+    free: u64,
+    reclaim: Reclaim,
+    stop: &AtomicBool,
+    observer: &mut dyn RunObserver,
+) -> Result<()> {
+    let ctx = DecisionContext {
+        working_dir: root.to_path_buf(),
+        is_dry_run: args.dry_run,
+        yes_to_all: true,
+        format: OutputFormat::Text,
+    };
+    let cleaner: Box<dyn DoCleanUp> = if args.dry_run {
+        Box::new(DryRunCleaner)
+    } else {
+        Box::new(ProperCleaner)
+    };
+    observer.on_event(&Event::new(
+        args.dry_run,
+        EventKind::DiskPressure {
+            path: root.display().to_string(),
+            free_bytes: free,
+            below_bytes: args.below,
+        },
+    ));
+    ctx.println(format!(
+        "{} free at {}, cleaning",
+        (free as usize).as_human_readable(),
+        root.display()
+    ));
+    let ranked = watch_candidates(args, root, stop)?;
+    let mut tally = RunTally::default();
+    let result = clean_folders(
+        &ctx,
+        ranked
+            .into_iter()
+            .take_while(|_| !stop.load(Ordering::Relaxed)),
+        &*cleaner,
+        &mut NiceInteractiveDecider::default(),
+        observer,
+        &mut tally,
+        Some(&reclaim),
+    );
+    let freed = tally.table.freed() as u64;
+    tally.summary.target_reached = Some(reclaim.is_met(freed, args.dry_run)?);
+    observer.on_event(&Event::new(args.dry_run, EventKind::Summary(tally.summary)));
+    result?;
+    ctx.println(reclaim.report(freed, args.dry_run)?);
+    Ok(())
+}
+
+/// The ranked candidates below `root`. Walking and sizing a large tree
+/// takes a while, so both end early once `stop` is set.
+fn watch_candidates(args: &WatchCliArgs, root: &Path, stop: &AtomicBool) -> Result<Vec<Candidate>> {
+    let hidden_policy =
+        HiddenPolicy::from_flags(args.dive_into_hidden_folders, args.no_hidden, &args.hidden)
+            .map_err(|msg| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg))?;
+    let owners = owner_filter(args.any_owner, args.owner.as_deref())?;
+    let folders = artifact_folders(
+        root.to_path_buf(),
+        args.follow,
+        args.parallelism.unwrap_or(DEFAULT_PARALLELISM),
+        args.older_than,
+        hidden_policy,
+        Arc::new(MountFilter::load(&args.include_fs)),
+    )
+    // checked before every folder the walk yields, so also before
+    // `rank_candidates` sizes the next one
+    .take_while(|_| !stop.load(Ordering::Relaxed))
+    .filter(move |(folder, _)| owners.allows(folder.as_ref()));
+    Ok(rank_candidates(folders, args.rank))
+}
+
+/// The ownership policy from `--any-owner` / `--owner`.
+fn owner_filter(any_owner: bool, owner: Option<&str>) -> Result<Arc<OwnerFilter>> {
+    OwnerFilter::new(any_owner, owner)
+        .map(Arc::new)
+        .map_err(|msg| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg))
+}
+
+/// The observer of a run: the highscore board for real runs, wrapped in a
+/// `Reporter` for the JSON formats.
+fn run_observer(dry_run: bool, format: OutputFormat) -> Result<Box<dyn RunObserver>> {
+    let mut observer: Box<dyn RunObserver> = if !dry_run {
+        #[cfg(feature = "highscore-board")]
+        {
+            Box::new(if format.is_machine_readable() {
+                HighscoreObserver::load_quietly()?
+            } else {
+                HighscoreObserver::load()?
+            })
+        }
+        #[cfg(not(feature = "highscore-board"))]
+        {
+            Box::new(NoOpObserver)
+        }
+    } else {
+        Box::new(NoOpObserver)
+    };
+    if format.is_machine_readable() {
+        observer = Box::new(Reporter::new(format, std::io::stdout(), observer));
+    }
+    Ok(observer)
+}
+
+fn visit_path(args: &PutzenCliArgs) -> Result<()> {
+    let folder = args
+        .folder
+        .canonicalize()
+        .expect("Folder cannot be canonicalized.");
+    let ctx = DecisionContext {
+        working_dir: folder.clone(),
+        is_dry_run: args.dry_run,
+        yes_to_all: args.yes_to_all,
+        format: args.format,
+    };
+
+    let cleaner: Box<dyn DoCleanUp> = if args.dry_run {
+        Box::new(DryRunCleaner)
+    } else {
+        Box::new(ProperCleaner)
+    };
+
+    let mut observer = run_observer(args.dry_run, args.format)?;
+    if let Some(plan_out) = &args.plan_out {
+        // created up front, so a bad path fails before the walk
+        let out = std::fs::File::create(plan_out)?;
+        observer = Box::new(PlanRecorder::new(&folder, out, observer));
+    }
+
+    let hidden_policy = HiddenPolicy::from_args(args)
+        .map_err(|msg| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg))?;
+
+    let goal = match (args.until_free, args.free_at_least) {
+        (Some(bytes), _) => Some(Goal::UntilFree(bytes)),
+        (None, Some(bytes)) => Some(Goal::FreeAtLeast(bytes)),
+        (None, None) => None,
+    };
+    let reclaim = goal
+        .map(|goal| Reclaim::start(goal, &folder, Box::new(Statvfs)))
+        .transpose()?;
+
+    ctx.println(format!("Start cleaning at {}", folder.display()));
+    let mounts = Arc::new(MountFilter::load(&args.include_fs));
+    let owners = owner_filter(args.any_owner, args.owner.as_deref())?;
+    let folders = artifact_folders(
+        folder,
+        args.follow,
+        args.parallelism.unwrap_or(DEFAULT_PARALLELISM),
+        args.older_than,
+        hidden_policy,
+        Arc::clone(&mounts),
+    )
+    .filter({
+        let owners = Arc::clone(&owners);
+        move |(folder, _)| owners.allows(folder.as_ref())
+    });
+    // with a goal, the least valuable bytes go first
+    let folders: Box<dyn Iterator<Item = Candidate>> = if reclaim.is_some() {
+        Box::new(rank_candidates(folders, Rank::Score).into_iter())
+    } else {
+        Box::new(folders)
+    };
+    let mut tally = RunTally::default();
+    let result = if args.edit {
+        let planned = plan_in_editor(folders)?;
+        clean_folders(
+            &ctx,
+            planned.into_iter(),
+            &*cleaner,
+            &mut PlannedDecider,
+            &mut *observer,
+            &mut tally,
+            None,
+        )
+    } else {
+        clean_folders(
+            &ctx,
+            folders,
+            &*cleaner,
+            &mut NiceInteractiveDecider::default(),
+            &mut *observer,
+            &mut tally,
+            reclaim.as_ref(),
+        )
+    };
+    let RunTally { mut summary, table } = tally;
+    let freed = table.freed() as u64;
+    summary.skipped_mounts = mounts.skipped();
+    summary.skipped_foreign = owners.skipped();
+    summary.target_reached = reclaim
+        .as_ref()
+        .map(|r| r.is_met(freed, args.dry_run))
+        .transpose()?;
+    observer.on_event(&Event::new(
+        args.dry_run,
+        EventKind::Summary(summary.clone()),
+    ));
+    result?;
+    // the user quit; leave without the summary, as always.
+    if summary.aborted {
+        return Ok(());
+    }
+
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    if !table.is_empty() {
+        ctx.println(table.render(args.dry_run, home.as_deref()));
+    }
+    if let Some(reclaim) = &reclaim {
+        ctx.println(reclaim.report(freed, args.dry_run)?);
+    }
+    if !summary.skipped_mounts.is_empty() {
+        ctx.println(format!(
+            "Skipped {} network or FUSE mount(s), pass `--include-fs <type>` to walk them:",
+            summary.skipped_mounts.len()
+        ));
+        for mount in &summary.skipped_mounts {
+            ctx.println(format!("  {} ({})", mount.path.display(), mount.fs_type));
+        }
+    }
+    print_skipped_foreign(&ctx, &summary.skipped_foreign);
+    print_freed(&ctx, &summary, &mut *observer);
+    if let Some(plan_out) = &args.plan_out {
+        ctx.println(format!(
+            "Plan written to {}, delete exactly these folders with `putzen apply {}`",
+            plan_out.display(),
+            plan_out.display()
+        ));
+    }
+
+    Ok(())
+}
+
+/// The folders left alone because other users own them.
+fn print_skipped_foreign(ctx: &DecisionContext, skipped: &[ForeignFolder]) {
+    if skipped.is_empty() {
+        return;
+    }
+    ctx.println(format!(
+        "Skipped {} folder(s) owned by other users, pass `--any-owner` or `--owner <user>` to clean them:",
+        skipped.len()
+    ));
+    for foreign in skipped {
+        let owner = foreign
+            .owner
+            .clone()
+            .unwrap_or_else(|| format!("uid {}", foreign.uid));
+        ctx.println(format!("  {} (owner {owner})", foreign.path.display()));
+    }
+}
+
+/// The closing lines of a run: what was freed and the highscore medals.
+fn print_freed(ctx: &DecisionContext, summary: &RunSummary, observer: &mut dyn RunObserver) {
+    let amount_cleaned = summary.freed_bytes as usize;
     if amount_cleaned > 0 {
         ctx.println(format!("Freed: {}", amount_cleaned.as_human_readable()));
     } else {
@@ -340,15 +1431,199 @@ fn visit_path(args: &PutzenCliArgs) -> Result<()> {
     }
 
     if let Some(medals) = observer.on_run_complete(amount_cleaned as u64) {
-        println!("{medals}");
+        ctx.println(medals);
+    }
+}
+
+/// `putzen apply`: check every folder of a plan against its fingerprint,
+/// then delete the unchanged ones. Any drift refuses the whole plan unless
+/// `--skip-drifted` is given. Returns `false` when something drifted.
+fn apply(args: &ApplyCliArgs) -> Result<bool> {
+    let owners = owner_filter(args.any_owner, args.owner.as_deref())?;
+    let text = std::fs::read_to_string(&args.plan)?;
+    let plan = PlanFile::parse(&text)
+        .map_err(|msg| std::io::Error::new(std::io::ErrorKind::InvalidData, msg))?;
+    let ctx = DecisionContext {
+        working_dir: plan.root.clone(),
+        is_dry_run: args.dry_run,
+        yes_to_all: true,
+        format: OutputFormat::Text,
+    };
+
+    let mut unchanged = Vec::new();
+    let mut drifted = 0;
+    for planned in &plan.candidates {
+        let rule = known_rule(planned);
+        match planned.verify(rule) {
+            Ok(folder) if !owners.allows(folder.as_ref()) => {}
+            Ok(folder) => unchanged.push((folder, rule.expect("verified with a rule"))),
+            Err(drift) => {
+                drifted += 1;
+                eprintln!("drift: {} {drift}", planned.path.display());
+            }
+        }
+    }
+    if drifted > 0 && !args.skip_drifted {
+        eprintln!(
+            "Refusing to apply {}: {drifted} of {} folders changed since the plan was made. \
+             Make a new plan, or pass --skip-drifted to delete only the unchanged ones.",
+            args.plan.display(),
+            plan.candidates.len()
+        );
+        return Ok(false);
     }
 
+    let cleaner: Box<dyn DoCleanUp> = if args.dry_run {
+        Box::new(DryRunCleaner)
+    } else {
+        Box::new(ProperCleaner)
+    };
+    let mut observer = run_observer(args.dry_run, OutputFormat::Text)?;
+    ctx.println(format!(
+        "Applying {} made at {} with {} folders",
+        args.plan.display(),
+        plan.created,
+        unchanged.len()
+    ));
+    let mut tally = RunTally::default();
+    clean_folders(
+        &ctx,
+        unchanged.into_iter(),
+        &*cleaner,
+        &mut PlannedDecider,
+        &mut *observer,
+        &mut tally,
+        None,
+    )?;
+    if !tally.table.is_empty() {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        ctx.println(tally.table.render(args.dry_run, home.as_deref()));
+    }
+    print_skipped_foreign(&ctx, &owners.skipped());
+    print_freed(&ctx, &tally.summary, &mut *observer);
+    Ok(drifted == 0)
+}
+
+/// The rule a plan file names, if this putzen still has it. An extra
+/// target only counts while the project's `.putzen.toml` still lists it; a
+/// hand-edited plan must not turn any folder next to one into a target.
+fn known_rule(planned: &PlannedFolder) -> Option<&'static FileToFolderMatch> {
+    let rule = &planned.rule;
+    if let Some(known) = FOLDER_TO_CLEANUP.iter().find(|known| rule.matches(known)) {
+        return Some(known);
+    }
+    if rule.file_to_check != project_config::FILE_NAME
+        || planned.path.file_name() != Some(rule.folder_to_remove.as_ref())
+    {
+        return None;
+    }
+    let project = planned.path.parent()?;
+    let text = std::fs::read_to_string(project.join(project_config::FILE_NAME)).ok()?;
+    let config = ProjectConfig::parse(&text).ok()?;
+    config
+        .extra_targets
+        .contains(&rule.folder_to_remove)
+        .then(|| project_config::extra_rule(&rule.folder_to_remove))
+}
+
+/// What `clean_folders` counted: the totals for the report and the
+/// per-ecosystem / per-project table.
+#[derive(Default)]
+struct RunTally {
+    summary: RunSummary,
+    table: RunTable,
+}
+
+/// Size every candidate and order them by `rank`, highest first.
+fn rank_candidates(folders: impl Iterator<Item = Candidate>, rank: Rank) -> Vec<Candidate> {
+    let now = SystemTime::now();
+    let mut scored: Vec<(f64, Candidate)> = folders
+        .map(|candidate| (rank.key(&candidate.0.stats(), now), candidate))
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+/// Offer every candidate to its rule and tally the outcome. A quit by the
+/// user or a failed deletion marks the run as aborted. With a `reclaim`
+/// goal the run stops as soon as the goal is met.
+fn clean_folders(
+    ctx: &DecisionContext,
+    folders: impl Iterator<Item = Candidate>,
+    cleaner: &dyn DoCleanUp,
+    decider: &mut impl Decide,
+    observer: &mut dyn RunObserver,
+    tally: &mut RunTally,
+    reclaim: Option<&Reclaim>,
+) -> Result<()> {
+    let RunTally { summary, table } = tally;
+    for (folder, rule) in folders {
+        if let Some(reclaim) = reclaim {
+            if reclaim.is_met(table.freed() as u64, ctx.is_dry_run)? {
+                break;
+            }
+        }
+        let mut recording = Recording {
+            table,
+            inner: &mut *observer,
+        };
+        let result = folder.accept(ctx, rule, cleaner, decider, &mut recording);
+        if !matches!(result, Ok(FolderProcessed::NoRuleMatch)) {
+            summary.found += 1;
+        }
+        match result {
+            Ok(FolderProcessed::Abort) => {
+                summary.aborted = true;
+                return Ok(());
+            }
+            Ok(FolderProcessed::Cleaned(size)) => {
+                summary.cleaned += 1;
+                summary.freed_bytes += size as u64;
+            }
+            Ok(FolderProcessed::NoRuleMatch) | Ok(FolderProcessed::Skipped) => {}
+            Err(error) => {
+                summary.failed += 1;
+                summary.aborted = true;
+                return Err(error);
+            }
+        };
+    }
     Ok(())
 }
 
+/// `--edit`: size every candidate, let the user edit the plan in `$EDITOR`
+/// and return the candidates still marked `delete`.
+fn plan_in_editor(folders: impl Iterator<Item = Candidate>) -> Result<Vec<Candidate>> {
+    let now = SystemTime::now();
+    let mut candidates: HashMap<PathBuf, Candidate> = HashMap::new();
+    let plan: Vec<PlanCandidate> = folders
+        .map(|(folder, rule)| {
+            let stats = caches::scan::stat_dir(folder.as_ref());
+            candidates.insert(stats.path.clone(), (folder, rule));
+            PlanCandidate {
+                age: stats.age(now),
+                size: stats.size_bytes as usize,
+                ecosystem: rule.ecosystem().to_string(),
+                path: stats.path,
+            }
+        })
+        .collect();
+    if plan.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let edited = edit_plan::edit(&edit_plan::render(&plan, home.as_deref()))?;
+    Ok(edit_plan::parse(&edited, &plan, home.as_deref())?
+        .into_iter()
+        .filter_map(|path| candidates.remove(&path))
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use putzen_cli::plan_file::PlannedRule;
 
     #[test]
     fn test_e2e_scenario() {
@@ -379,8 +1654,20 @@ mod tests {
             #[cfg(feature = "highscore-board")]
             scores: false,
             dry_run: false,
+            no_dry_run: false,
             yes_to_all: true,
             follow: false,
+            parallelism: None,
+            older_than: None,
+            until_free: None,
+            free_at_least: None,
+            tui: false,
+            edit: false,
+            format: OutputFormat::Text,
+            any_owner: false,
+            owner: None,
+            include_fs: Vec::new(),
+            plan_out: None,
             dive_into_hidden_folders: false,
             no_hidden: false,
             hidden: Vec::new(),
@@ -494,6 +1781,450 @@ mod tests {
         assert!(!policy.allows_hidden(".git".as_ref()));
     }
 
+    #[test]
+    fn from_args_tui_switch() {
+        assert!(!args_from(&[]).unwrap().tui);
+        assert!(args_from(&["--tui", "-d"]).unwrap().tui);
+    }
+
+    #[test]
+    fn from_args_format_option() {
+        assert_eq!(args_from(&[]).unwrap().format, OutputFormat::Text);
+        let args = args_from(&["--format", "ndjson"]).unwrap();
+        assert_eq!(args.format, OutputFormat::Ndjson);
+        assert!(args_from(&["--format", "yaml"]).is_err());
+    }
+
+    #[test]
+    fn from_args_space_goals() {
+        let args = args_from(&["--until-free", "50G"]).unwrap();
+        assert_eq!(args.until_free, Some(50 << 30));
+        assert_eq!(args.free_at_least, None);
+        let args = args_from(&["--free-at-least", "1.5M"]).unwrap();
+        assert_eq!(args.free_at_least, Some(3 << 19));
+        assert!(args_from(&["--until-free", "lots"]).is_err());
+    }
+
+    #[test]
+    fn rank_candidates_puts_big_old_folders_first() {
+        static RULE: FileToFolderMatch = FileToFolderMatch::new("Cargo.toml", "target");
+        let tmp = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        let folder = |name: &str, bytes: usize, days: u64| {
+            let dir = tmp.path().join(name).join("target");
+            std::fs::create_dir_all(&dir).unwrap();
+            let file = dir.join("artefact");
+            std::fs::write(&file, vec![0u8; bytes]).unwrap();
+            let mtime = now - Duration::from_secs(days * 86_400);
+            filetime::set_file_mtime(&file, filetime::FileTime::from_system_time(mtime)).unwrap();
+            (Folder::try_from(dir).unwrap(), &RULE)
+        };
+        let candidates = vec![
+            folder("fresh", 4096, 0),
+            folder("old-small", 1024, 300),
+            folder("old-big", 4096, 300),
+        ];
+
+        let ranked: Vec<String> = rank_candidates(candidates.into_iter(), Rank::Score)
+            .iter()
+            .map(|(f, _)| {
+                let project = f.as_ref().parent().unwrap().file_name().unwrap();
+                project.to_string_lossy().into_owned()
+            })
+            .collect();
+        assert_eq!(ranked, ["old-big", "old-small", "fresh"]);
+    }
+
+    #[test]
+    fn list_args_parse_sort_top_and_min_size() {
+        let args = ListCliArgs::from_args(
+            &["putzen list"],
+            &["--sort", "age", "--top", "5", "--min-size", "1G", "/tmp"],
+        )
+        .unwrap();
+        assert_eq!(args.sort, ListSort::Age);
+        assert_eq!(args.top, Some(5));
+        assert_eq!(args.min_size, 1 << 30);
+        assert!(ListCliArgs::from_args(&["putzen list"], &["--sort", "name"]).is_err());
+    }
+
+    #[test]
+    fn read_only_subcommands_take_include_fs() {
+        let include = ["--include-fs", "nfs", "--include-fs", "sshfs"];
+        let list = ListCliArgs::from_args(&["putzen list"], &include).unwrap();
+        assert_eq!(list.include_fs, ["nfs", "sshfs"]);
+        let mut input = vec!["--max-reclaimable", "1G"];
+        input.extend(include);
+        let check = CheckCliArgs::from_args(&["putzen check"], &input).unwrap();
+        assert_eq!(check.include_fs, ["nfs", "sshfs"]);
+        let metrics = MetricsCliArgs::from_args(&["putzen metrics"], &include).unwrap();
+        assert_eq!(metrics.include_fs, ["nfs", "sshfs"]);
+        let mut input = vec!["--below", "1G"];
+        input.extend(include);
+        let watch = WatchCliArgs::from_args(&["putzen watch"], &input).unwrap();
+        assert_eq!(watch.include_fs, ["nfs", "sshfs"]);
+    }
+
+    #[test]
+    fn cli_hidden_is_none_without_hidden_flags() {
+        let args = args_from(&[]).unwrap();
+        assert_eq!(cli_hidden(false, false, &args.hidden), None);
+        assert_eq!(cli_hidden(true, false, &[]), Some(vec!["*".to_string()]));
+        assert_eq!(cli_hidden(false, true, &[]), Some(Vec::new()));
+        let args = args_from(&["--hidden", ".jj"]).unwrap();
+        assert_eq!(
+            cli_hidden(false, false, &args.hidden),
+            Some(vec![".jj".to_string()])
+        );
+    }
+
+    #[test]
+    fn cli_dry_run_can_turn_a_configured_dry_run_off() {
+        assert_eq!(cli_dry_run(false, false), None);
+        assert_eq!(cli_dry_run(true, false), Some(true));
+        assert_eq!(cli_dry_run(false, true), Some(false));
+        assert_eq!(
+            cli_dry_run(true, true),
+            Some(true),
+            "dry run is the safe side"
+        );
+    }
+
+    #[test]
+    fn artifact_folders_yields_matches_without_descending() {
+        let root = tempfile::TempDir::new().unwrap();
+        std::fs::File::create(root.path().join("Cargo.toml")).unwrap();
+        // a nested project inside `target` must not be reported on its own
+        let nested = root.path().join("target").join("nested");
+        std::fs::create_dir_all(nested.join("node_modules")).unwrap();
+        std::fs::File::create(nested.join("package.json")).unwrap();
+
+        let policy = HiddenPolicy::from_args(&args_from(&[]).unwrap()).unwrap();
+        let found: Vec<Candidate> = artifact_folders(
+            root.path().canonicalize().unwrap(),
+            false,
+            2,
+            None,
+            policy,
+            Default::default(),
+        )
+        .collect();
+        assert_eq!(found.len(), 1);
+        assert!(found[0].0.as_ref().ends_with("target"));
+        assert_eq!(found[0].1.ecosystem(), "rust");
+    }
+
+    #[test]
+    fn artifact_folders_skip_remote_mounts_and_report_them() {
+        let root = tempfile::TempDir::new().unwrap();
+        let root = root.path().canonicalize().unwrap();
+        rust_project(&root, "local", 10);
+        rust_project(&root.join("nfs"), "remote", 10);
+        let mountinfo = format!(
+            "40 22 0:45 / {} rw - nfs4 server:/export rw\n",
+            root.join("nfs").display()
+        );
+        let mounts = Arc::new(MountFilter::from_mountinfo(&mountinfo, &[]));
+        let policy = HiddenPolicy::from_args(&args_from(&[]).unwrap()).unwrap();
+
+        let found: Vec<Candidate> =
+            artifact_folders(root.clone(), false, 2, None, policy, Arc::clone(&mounts)).collect();
+        assert_eq!(found.len(), 1);
+        assert!(found[0].0.as_ref().starts_with(root.join("local")));
+        let skipped = mounts.skipped();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].path, root.join("nfs"));
+        assert_eq!(skipped[0].fs_type, "nfs4");
+    }
+
+    /// Free space that only grows when the test says so.
+    struct FakeDisk(u64);
+
+    impl FreeSpace for FakeDisk {
+        fn available_bytes(&self, _path: &Path) -> Result<u64> {
+            Ok(self.0)
+        }
+    }
+
+    fn rust_project(root: &Path, name: &str, bytes: usize) -> PathBuf {
+        let target = root.join(name).join("target");
+        std::fs::create_dir_all(&target).unwrap();
+        std::fs::File::create(root.join(name).join("Cargo.toml")).unwrap();
+        std::fs::write(target.join("artefact"), vec![0u8; bytes]).unwrap();
+        target
+    }
+
+    #[test]
+    fn watch_cleanup_deletes_until_the_goal_and_journals_it() {
+        let root = tempfile::TempDir::new().unwrap();
+        let big = rust_project(root.path(), "big", 4096);
+        let small = rust_project(root.path(), "small", 1024);
+        let args = WatchCliArgs::from_args(&["putzen watch"], &["--below", "1G", "--rank", "size"])
+            .unwrap();
+        let root = root.path().canonicalize().unwrap();
+        // the disk never reports more room, so only the freed bytes count
+        let reclaim =
+            Reclaim::start(Goal::FreeAtLeast(4096), &root, Box::new(FakeDisk(0))).unwrap();
+        let mut journal = Reporter::new(OutputFormat::Ndjson, Vec::new(), Box::new(NoOpObserver));
+
+        watch_cleanup(
+            &args,
+            &root,
+            0,
+            reclaim,
+            &AtomicBool::new(false),
+            &mut journal,
+        )
+        .unwrap();
+
+        assert!(!big.exists(), "the largest folder goes first");
+        assert!(small.exists(), "the goal was met before the next one");
+        let journal = String::from_utf8(journal.into_writer()).unwrap();
+        let events: Vec<serde_json::Value> = journal
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(events[0]["event"], "disk_pressure");
+        assert!(events.iter().any(|e| e["event"] == "cleaned"));
+        let summary = events.last().unwrap();
+        assert_eq!(summary["event"], "summary");
+        assert_eq!(summary["target_reached"], true);
+    }
+
+    #[test]
+    fn watch_cleanup_stops_when_asked() {
+        let root = tempfile::TempDir::new().unwrap();
+        let target = rust_project(root.path(), "app", 1024);
+        let args = WatchCliArgs::from_args(&["putzen watch"], &["--below", "1G"]).unwrap();
+        let root = root.path().canonicalize().unwrap();
+        let reclaim =
+            Reclaim::start(Goal::UntilFree(1 << 30), &root, Box::new(FakeDisk(0))).unwrap();
+
+        let stop = AtomicBool::new(true);
+        assert!(
+            watch_candidates(&args, &root, &stop).unwrap().is_empty(),
+            "nothing is walked or sized once asked to stop"
+        );
+        assert_eq!(
+            watch_candidates(&args, &root, &AtomicBool::new(false))
+                .unwrap()
+                .len(),
+            1
+        );
+
+        watch_cleanup(&args, &root, 0, reclaim, &stop, &mut NoOpObserver).unwrap();
+        assert!(target.exists());
+    }
+
+    #[test]
+    fn plan_out_then_apply_deletes_exactly_the_planned_folders() {
+        let root = tempfile::TempDir::new().unwrap();
+        let app = rust_project(root.path(), "app", 1024);
+        let plan = root.path().join("plan.json");
+        let mut args = args_from(&["-d", "-y"]).unwrap();
+        args.plan_out = Some(plan.clone());
+        args.folder = root.path().to_path_buf();
+        visit_path(&args).unwrap();
+        assert!(app.exists(), "a dry-run deletes nothing");
+
+        // a folder added after the plan was made is not part of it
+        let late = rust_project(root.path(), "late", 1024);
+        let apply_args = |extra: &[&str]| {
+            let mut input = extra.to_vec();
+            input.push(plan.to_str().unwrap());
+            ApplyCliArgs::from_args(&["putzen apply"], &input).unwrap()
+        };
+        assert!(apply(&apply_args(&[])).unwrap());
+        assert!(!app.exists());
+        assert!(late.exists());
+    }
+
+    #[test]
+    fn apply_refuses_a_drifted_plan() {
+        let root = tempfile::TempDir::new().unwrap();
+        let app = rust_project(root.path(), "app", 1024);
+        let lib = rust_project(root.path(), "lib", 1024);
+        let plan = root.path().join("plan.json");
+        let mut args = args_from(&["-d", "-y"]).unwrap();
+        args.plan_out = Some(plan.clone());
+        args.folder = root.path().to_path_buf();
+        visit_path(&args).unwrap();
+
+        std::fs::write(lib.join("artefact"), vec![0u8; 10]).unwrap();
+        let plan = plan.to_str().unwrap();
+        let refuse = ApplyCliArgs::from_args(&["putzen apply"], &[plan]).unwrap();
+        assert!(!apply(&refuse).unwrap());
+        assert!(app.exists() && lib.exists(), "drift refuses the whole plan");
+
+        let skip = ApplyCliArgs::from_args(&["putzen apply"], &["--skip-drifted", plan]).unwrap();
+        assert!(!apply(&skip).unwrap());
+        assert!(!app.exists());
+        assert!(lib.exists());
+    }
+
+    #[test]
+    fn plans_only_name_extra_targets_the_project_still_lists() {
+        let root = tempfile::TempDir::new().unwrap();
+        let putzen_toml = root.path().join(project_config::FILE_NAME);
+        std::fs::write(&putzen_toml, "extra_targets = [\"generated\"]").unwrap();
+        let planned = |name: &str| PlannedFolder {
+            path: root.path().join(name),
+            size_bytes: 0,
+            newest_mtime: None,
+            rule: PlannedRule {
+                ecosystem: project_config::EXTRA_ECOSYSTEM.into(),
+                file_to_check: project_config::FILE_NAME.into(),
+                folder_to_remove: name.into(),
+            },
+        };
+        assert!(known_rule(&planned("generated")).is_some());
+        assert!(known_rule(&planned("src")).is_none(), "not listed");
+
+        std::fs::write(&putzen_toml, "keep = false").unwrap();
+        assert!(known_rule(&planned("generated")).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn apply_leaves_planned_folders_of_other_users_alone() {
+        use std::os::unix::fs::MetadataExt;
+
+        let root = tempfile::TempDir::new().unwrap();
+        let app = rust_project(root.path(), "app", 10);
+        let plan = root.path().join("plan.json");
+        let mut args = args_from(&["-d", "-y"]).unwrap();
+        args.plan_out = Some(plan.clone());
+        args.folder = root.path().to_path_buf();
+        visit_path(&args).unwrap();
+
+        let someone_else = (std::fs::metadata(&app).unwrap().uid() + 1).to_string();
+        let plan = plan.to_str().unwrap();
+        let args =
+            ApplyCliArgs::from_args(&["putzen apply"], &["--owner", &someone_else, plan]).unwrap();
+        assert!(apply(&args).unwrap());
+        assert!(app.exists());
+
+        let args = ApplyCliArgs::from_args(&["putzen apply"], &["--any-owner", plan]).unwrap();
+        assert!(apply(&args).unwrap());
+        assert!(!app.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn foreign_owned_folders_are_left_alone() {
+        use std::os::unix::fs::MetadataExt;
+
+        let root = tempfile::TempDir::new().unwrap();
+        let app = rust_project(root.path(), "app", 10);
+        let someone_else = (std::fs::metadata(&app).unwrap().uid() + 1).to_string();
+        let mut args = args_from(&["-y", "--owner", &someone_else]).unwrap();
+        args.folder = root.path().to_path_buf();
+        visit_path(&args).unwrap();
+        assert!(app.exists());
+
+        let mut args = args_from(&["-y", "--any-owner"]).unwrap();
+        args.folder = root.path().to_path_buf();
+        visit_path(&args).unwrap();
+        assert!(!app.exists());
+    }
+
+    #[test]
+    fn check_fails_only_over_budget() {
+        let root = tempfile::TempDir::new().unwrap();
+        let app = rust_project(root.path(), "app", 2048);
+        let folder = root.path().to_str().unwrap();
+        let args = |budget: &str| {
+            CheckCliArgs::from_args(&["putzen check"], &["--max-reclaimable", budget, folder])
+                .unwrap()
+        };
+        assert!(check(&args("2K")).unwrap());
+        assert!(!check(&args("1K")).unwrap());
+        assert!(app.exists(), "check never deletes");
+    }
+
+    #[test]
+    fn watch_args_defaults() {
+        let args = WatchCliArgs::from_args(&["putzen watch"], &["--below", "20G"]).unwrap();
+        assert_eq!(args.below, 20 << 30);
+        assert_eq!(args.until, None);
+        assert_eq!(args.interval, Duration::from_secs(300));
+        assert_eq!(args.rank, Rank::Score);
+        assert!(WatchCliArgs::from_args(&["putzen watch"], &[]).is_err());
+    }
+
+    fn walk(root: &std::path::Path, older_than: Option<Duration>) -> Vec<PathBuf> {
+        let policy = HiddenPolicy::from_args(&args_from(&[]).unwrap()).unwrap();
+        let mut found: Vec<PathBuf> = artifact_folders(
+            root.canonicalize().unwrap(),
+            false,
+            2,
+            older_than,
+            policy,
+            Default::default(),
+        )
+        .map(|(folder, _)| folder.as_ref().to_path_buf())
+        .collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn putzen_toml_keep_protects_only_its_subtree() {
+        let root = tempfile::TempDir::new().unwrap();
+        for project in ["vendored", "other"] {
+            let dir = root.path().join(project);
+            std::fs::create_dir_all(dir.join("build")).unwrap();
+            std::fs::File::create(dir.join("CMakeLists.txt")).unwrap();
+        }
+        std::fs::write(
+            root.path().join("vendored").join(project_config::FILE_NAME),
+            "keep = true",
+        )
+        .unwrap();
+
+        let found = walk(root.path(), None);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].ends_with("other/build"));
+    }
+
+    #[test]
+    fn putzen_toml_adds_extra_targets() {
+        let root = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(root.path().join("generated")).unwrap();
+        std::fs::create_dir_all(root.path().join("sub").join("generated")).unwrap();
+        std::fs::write(
+            root.path().join(project_config::FILE_NAME),
+            "extra_targets = [\"generated\"]",
+        )
+        .unwrap();
+
+        let found = walk(root.path(), None);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].ends_with("generated"));
+        assert!(!found[0].ends_with("sub/generated"));
+    }
+
+    #[test]
+    fn putzen_toml_overrides_older_than_for_its_subtree() {
+        let root = tempfile::TempDir::new().unwrap();
+        for project in ["relaxed", "strict"] {
+            let dir = root.path().join(project);
+            std::fs::create_dir_all(dir.join("target")).unwrap();
+            std::fs::File::create(dir.join("Cargo.toml")).unwrap();
+            std::fs::write(dir.join("target").join("fresh"), "x").unwrap();
+        }
+        std::fs::write(
+            root.path().join("relaxed").join(project_config::FILE_NAME),
+            "older_than = \"0d\"",
+        )
+        .unwrap();
+
+        // the fresh `strict/target` is too young for the global 7 days
+        let found = walk(root.path(), Some(Duration::from_secs(7 * 86_400)));
+        assert_eq!(found.len(), 1);
+        assert!(found[0].ends_with("relaxed/target"));
+    }
+
     #[test]
     fn from_args_dash_a_includes_everything_hidden() {
         let args = args_from(&["-a"]).unwrap();
@@ -615,8 +2346,20 @@ mod tests {
             #[cfg(feature = "highscore-board")]
             scores: false,
             dry_run: false,
+            no_dry_run: false,
             yes_to_all: true,
             follow: false,
+            parallelism: None,
+            older_than: None,
+            until_free: None,
+            free_at_least: None,
+            tui: false,
+            edit: false,
+            format: OutputFormat::Text,
+            any_owner: false,
+            owner: None,
+            include_fs: Vec::new(),
+            plan_out: None,
             dive_into_hidden_folders: false,
             no_hidden: false,
             hidden: Vec::new(),
@@ -651,8 +2394,20 @@ mod tests {
             #[cfg(feature = "highscore-board")]
             scores: false,
             dry_run: false,
+            no_dry_run: false,
             yes_to_all: true,
             follow: false,
+            parallelism: None,
+            older_than: None,
+            until_free: None,
+            free_at_least: None,
+            tui: false,
+            edit: false,
+            format: OutputFormat::Text,
+            any_owner: false,
+            owner: None,
+            include_fs: Vec::new(),
+            plan_out: None,
             dive_into_hidden_folders: false,
             no_hidden: true,
             hidden: Vec::new(),
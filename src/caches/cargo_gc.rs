@@ -0,0 +1,345 @@
+//! Cargo registry garbage collection driven by the `Cargo.lock` files of
+//! the user's projects.
+//!
+//! Deleting all of `~/.cargo/registry` forces a full re-download. Instead,
+//! every `Cargo.lock` below the `--project-root`s tells which crate versions
+//! and git revisions are still in use; the rest of
+//!
+//! - `registry/cache/<index>/<name>-<version>.crate`,
+//! - `registry/src/<index>/<name>-<version>/` and
+//! - `git/checkouts/<repo>-<hash>/<short rev>/`
+//!
+//! shows up as a virtual "unreferenced" row when drilling into those
+//! folders in the caches TUI.
+
+use super::cargo_last_use::LastUse;
+use super::model::Cache;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Label of the virtual row.
+pub const UNREFERENCED: &str = "unreferenced";
+
+/// Folders never searched for lockfiles: build output, JS deps, VCS data.
+const SKIP_DIRS: [&str; 2] = ["target", "node_modules"];
+
+#[derive(Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockedPackage>,
+}
+
+#[derive(Deserialize)]
+struct LockedPackage {
+    name: String,
+    version: String,
+    /// `None` for path dependencies and the workspace members.
+    source: Option<String>,
+}
+
+/// Crate versions and git revisions referenced by a set of lockfiles.
+#[derive(Debug, Default)]
+pub struct Referenced {
+    crates: HashSet<(String, String)>,
+    /// Full commit hashes of git dependencies.
+    git_revs: HashSet<String>,
+    /// How many lockfiles went in.
+    pub lockfiles: usize,
+}
+
+impl Referenced {
+    /// Parse every `Cargo.lock` below `roots`. A lockfile that can't be
+    /// parsed is an error naming it, since skipping it would offer its
+    /// crates for deletion.
+    pub fn load(roots: &[PathBuf]) -> io::Result<Self> {
+        let mut refs = Self::default();
+        for lockfile in find_lockfiles(roots) {
+            let text = std::fs::read_to_string(&lockfile)?;
+            refs.add_lockfile(&text).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {e}", lockfile.display()),
+                )
+            })?;
+        }
+        Ok(refs)
+    }
+
+    pub fn add_lockfile(&mut self, text: &str) -> Result<(), String> {
+        let lock: Lockfile = toml::from_str(text).map_err(|e| e.to_string())?;
+        for package in lock.package {
+            let Some(source) = package.source else {
+                continue;
+            };
+            if let Some(git) = source.strip_prefix("git+") {
+                if let Some((_, rev)) = git.rsplit_once('#') {
+                    self.git_revs.insert(rev.to_string());
+                }
+            } else {
+                self.crates.insert((package.name, package.version));
+            }
+        }
+        self.lockfiles += 1;
+        Ok(())
+    }
+
+    /// `name-version` as cargo names `.crate` files and `src` folders. Names
+    /// may contain `-` too, so try every `-` that starts a version.
+    fn crate_is_referenced(&self, name_version: &str) -> bool {
+        name_version.match_indices('-').any(|(i, _)| {
+            let (name, version) = (&name_version[..i], &name_version[i + 1..]);
+            version.starts_with(|c: char| c.is_ascii_digit())
+                && self
+                    .crates
+                    .contains(&(name.to_string(), version.to_string()))
+        })
+    }
+
+    fn rev_is_referenced(&self, short: &str) -> bool {
+        self.git_revs.iter().any(|rev| rev.starts_with(short))
+    }
+
+    /// The entries of `dir` no lockfile references, or `None` when `dir` is
+    /// not one of the cargo folders this analyzer understands.
+    pub fn unreferenced(&self, dir: &Path) -> Option<Vec<Cache>> {
+        let kind = Kind::of(dir)?;
+        let Ok(read) = std::fs::read_dir(dir) else {
+            return Some(Vec::new());
+        };
+        let mut out = Vec::new();
+        for entry in read.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+            let unused = match kind {
+                Kind::CrateFiles => match name.strip_suffix(".crate") {
+                    Some(stem) if !is_dir => !self.crate_is_referenced(stem),
+                    _ => false,
+                },
+                Kind::CrateSources => is_dir && !self.crate_is_referenced(&name),
+                Kind::GitCheckouts => is_dir && !self.rev_is_referenced(&name),
+            };
+            if unused {
+                out.extend(super::scan::stat_entry(&entry.path(), is_dir));
+            }
+        }
+        Some(out)
+    }
+
+    /// The virtual "unreferenced" row for `dir`, `None` when `dir` is not a
+    /// cargo folder or everything in it is still in use. Member ages come
+    /// from `last_use` where cargo tracks them.
+    pub fn unreferenced_row(&self, dir: &Path, last_use: Option<&LastUse>) -> Option<Cache> {
+        let mut members = self.unreferenced(dir)?;
+        if let Some(last_use) = last_use {
+            last_use.apply(&mut members);
+        }
+        (!members.is_empty()).then(|| virtual_row(dir, members))
+    }
+}
+
+/// Which cargo folder a directory is, judged by where it sits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    /// `registry/cache/<index>`
+    CrateFiles,
+    /// `registry/src/<index>`
+    CrateSources,
+    /// `git/checkouts/<repo>-<hash>`
+    GitCheckouts,
+}
+
+impl Kind {
+    fn of(dir: &Path) -> Option<Self> {
+        let parent = dir.parent()?;
+        let name = |p: &Path| p.file_name().map(|n| n.to_string_lossy().to_string());
+        match (name(parent)?.as_str(), name(parent.parent()?)?.as_str()) {
+            ("cache", "registry") => Some(Kind::CrateFiles),
+            ("src", "registry") => Some(Kind::CrateSources),
+            ("checkouts", "git") => Some(Kind::GitCheckouts),
+            _ => None,
+        }
+    }
+}
+
+/// The "unreferenced" row of `dir`.
+pub fn virtual_row(dir: &Path, members: Vec<Cache>) -> Cache {
+    Cache::virtual_row(UNREFERENCED.to_string(), dir.join(UNREFERENCED), members)
+}
+
+/// Every `Cargo.lock` below `roots`. A serial walk that skips hidden
+/// folders, `target` and `node_modules`, and never follows symlinks.
+pub fn find_lockfiles(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut dirs: Vec<PathBuf> = roots.to_vec();
+    while let Some(dir) = dirs.pop() {
+        let Ok(read) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in read.flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if !name.starts_with('.') && !SKIP_DIRS.contains(&name.as_ref()) {
+                    dirs.push(entry.path());
+                }
+            } else if file_type.is_file() && name == "Cargo.lock" {
+                found.push(entry.path());
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const LOCK: &str = r#"
+version = 3
+
+[[package]]
+name = "my-app"
+version = "0.1.0"
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "wasm-bindgen"
+version = "0.2.92"
+source = "sparse+https://index.crates.io/"
+
+[[package]]
+name = "ratatui"
+version = "0.30.0"
+source = "git+https://github.com/ratatui/ratatui?branch=main#0123456789abcdef0123456789abcdef01234567"
+"#;
+
+    fn refs() -> Referenced {
+        let mut refs = Referenced::default();
+        refs.add_lockfile(LOCK).unwrap();
+        refs
+    }
+
+    #[test]
+    fn lockfile_yields_crates_and_git_revisions() {
+        let refs = refs();
+        assert!(refs.crate_is_referenced("serde-1.0.200"));
+        assert!(refs.crate_is_referenced("wasm-bindgen-0.2.92"));
+        assert!(!refs.crate_is_referenced("serde-1.0.100"));
+        assert!(
+            !refs.crate_is_referenced("my-app-0.1.0"),
+            "path deps are not cached"
+        );
+        assert!(refs.rev_is_referenced("0123456"));
+        assert!(!refs.rev_is_referenced("fedcba9"));
+    }
+
+    #[test]
+    fn malformed_lockfile_is_an_error() {
+        assert!(Referenced::default()
+            .add_lockfile("[[package]]\nname = 1\n")
+            .is_err());
+    }
+
+    fn cargo_home() -> tempfile::TempDir {
+        let home = tempfile::tempdir().unwrap();
+        let index = "index.crates.io-1949cf8c6b5b557f";
+        let cache = home.path().join("registry/cache").join(index);
+        fs::create_dir_all(&cache).unwrap();
+        fs::write(cache.join("serde-1.0.200.crate"), [0u8; 10]).unwrap();
+        fs::write(cache.join("serde-1.0.100.crate"), [0u8; 20]).unwrap();
+        fs::write(cache.join("wasm-bindgen-0.2.80.crate"), [0u8; 40]).unwrap();
+        let src = home.path().join("registry/src").join(index);
+        fs::create_dir_all(src.join("serde-1.0.200")).unwrap();
+        fs::create_dir_all(src.join("serde-1.0.100")).unwrap();
+        fs::write(src.join("serde-1.0.100/lib.rs"), [0u8; 5]).unwrap();
+        let checkouts = home.path().join("git/checkouts/ratatui-a1b2c3d4e5f6");
+        fs::create_dir_all(checkouts.join("0123456")).unwrap();
+        fs::create_dir_all(checkouts.join("fedcba9")).unwrap();
+        home
+    }
+
+    fn labels(caches: &[Cache]) -> Vec<String> {
+        let mut labels: Vec<_> = caches.iter().map(|c| c.label.clone()).collect();
+        labels.sort();
+        labels
+    }
+
+    #[test]
+    fn finds_unreferenced_crate_files_sources_and_checkouts() {
+        let home = cargo_home();
+        let refs = refs();
+        let index = "index.crates.io-1949cf8c6b5b557f";
+
+        let cache = refs
+            .unreferenced(&home.path().join("registry/cache").join(index))
+            .unwrap();
+        assert_eq!(
+            labels(&cache),
+            ["serde-1.0.100.crate", "wasm-bindgen-0.2.80.crate"]
+        );
+
+        let src = refs
+            .unreferenced(&home.path().join("registry/src").join(index))
+            .unwrap();
+        assert_eq!(labels(&src), ["serde-1.0.100"]);
+
+        let git = refs
+            .unreferenced(&home.path().join("git/checkouts/ratatui-a1b2c3d4e5f6"))
+            .unwrap();
+        assert_eq!(labels(&git), ["fedcba9"]);
+    }
+
+    #[test]
+    fn virtual_row_sums_its_members() {
+        let home = cargo_home();
+        let dir = home
+            .path()
+            .join("registry/cache/index.crates.io-1949cf8c6b5b557f");
+        let row = refs().unreferenced_row(&dir, None).unwrap();
+        assert_eq!(row.label, UNREFERENCED);
+        assert_eq!(row.size_bytes, 60);
+        assert_eq!(row.members.len(), 2);
+        assert_eq!(row.top_files[0].name, "wasm-bindgen-0.2.80.crate");
+    }
+
+    #[test]
+    fn other_folders_are_not_analyzed() {
+        let home = cargo_home();
+        assert!(refs().unreferenced(&home.path().join("registry")).is_none());
+        assert!(refs().unreferenced_row(home.path(), None).is_none());
+    }
+
+    #[test]
+    fn lockfiles_are_found_outside_target_and_hidden_dirs() {
+        let root = tempfile::tempdir().unwrap();
+        for dir in ["app", "app/target/package/dep", ".hidden/x", "libs/core"] {
+            fs::create_dir_all(root.path().join(dir)).unwrap();
+            fs::write(root.path().join(dir).join("Cargo.lock"), LOCK).unwrap();
+        }
+        let found = find_lockfiles(&[root.path().to_path_buf()]);
+        assert_eq!(
+            found,
+            [
+                root.path().join("app/Cargo.lock"),
+                root.path().join("libs/core/Cargo.lock")
+            ]
+        );
+        assert_eq!(
+            Referenced::load(&[root.path().to_path_buf()])
+                .unwrap()
+                .lockfiles,
+            2
+        );
+    }
+}
@@ -0,0 +1,265 @@
+//! Last-use times from cargo's global cache tracker.
+//!
+//! Since 1.78 cargo records in `$CARGO_HOME/.global-cache` (SQLite) when it
+//! last used every registry index, `.crate` file, extracted source folder,
+//! git database and git checkout. File mtimes only tell when something was
+//! downloaded, so when drilling into the cargo folders these times replace
+//! `newest_mtime`, and with it the age and the score of a row.
+//!
+//! Reading the tracker needs SQLite, behind the default `cargo-last-use`
+//! feature. Without it [`LastUse::load`] finds nothing and ages stay mtimes.
+
+use super::model::Cache;
+#[cfg(feature = "cargo-last-use")]
+use rusqlite::{Connection, OpenFlags};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "cargo-last-use")]
+use std::time::Duration;
+use std::time::SystemTime;
+
+/// File name of the tracker database inside `$CARGO_HOME`.
+pub const DB_FILE: &str = ".global-cache";
+
+/// Each tracker table with the folder its names live in, relative to
+/// `$CARGO_HOME`. The first column names the index or git database folder
+/// in between, if any.
+#[cfg(feature = "cargo-last-use")]
+const TABLES: [(&str, &str); 5] = [
+    (
+        "registry/index",
+        "SELECT NULL, name, timestamp FROM registry_index",
+    ),
+    (
+        "registry/cache",
+        "SELECT i.name, c.name, c.timestamp FROM registry_crate c \
+         JOIN registry_index i ON i.id = c.registry_id",
+    ),
+    (
+        "registry/src",
+        "SELECT i.name, s.name, s.timestamp FROM registry_src s \
+         JOIN registry_index i ON i.id = s.registry_id",
+    ),
+    ("git/db", "SELECT NULL, name, timestamp FROM git_db"),
+    (
+        "git/checkouts",
+        "SELECT d.name, c.name, c.timestamp FROM git_checkout c \
+         JOIN git_db d ON d.id = c.git_id",
+    ),
+];
+
+/// When cargo last used each entry it tracks, and each folder above them
+/// up to `$CARGO_HOME`, by absolute path.
+#[derive(Debug, Default)]
+pub struct LastUse {
+    times: HashMap<PathBuf, SystemTime>,
+}
+
+impl LastUse {
+    /// The tracker of `cargo_home`, `None` when there is none (cargo older
+    /// than 1.78) or it can't be read; ages then come from mtimes as before.
+    #[cfg(feature = "cargo-last-use")]
+    pub fn load(cargo_home: &Path) -> Option<Self> {
+        let db = cargo_home.join(DB_FILE);
+        if !db.is_file() {
+            return None;
+        }
+        let cargo_home = std::fs::canonicalize(cargo_home).unwrap_or_else(|_| cargo_home.into());
+        Self::open(&db, &cargo_home).ok()
+    }
+
+    /// Built without SQLite: ages come from mtimes.
+    #[cfg(not(feature = "cargo-last-use"))]
+    pub fn load(_cargo_home: &Path) -> Option<Self> {
+        None
+    }
+
+    /// Read the tracker at `db`, resolving its names against `cargo_home`.
+    #[cfg(feature = "cargo-last-use")]
+    pub fn open(db: &Path, cargo_home: &Path) -> rusqlite::Result<Self> {
+        let conn = Connection::open_with_flags(db, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let mut entries = Vec::new();
+        for (dir, sql) in TABLES {
+            let mut stmt = conn.prepare(sql)?;
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, Option<String>>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })?;
+            for row in rows {
+                let (parent, name, secs) = row?;
+                let mut path = cargo_home.join(dir);
+                path.extend(parent);
+                path.push(name);
+                let when = SystemTime::UNIX_EPOCH + Duration::from_secs(secs.max(0) as u64);
+                entries.push((path, when));
+            }
+        }
+        Ok(Self::from_entries(cargo_home, entries))
+    }
+
+    /// Every entry at its own time, and every folder between an entry and
+    /// `cargo_home` at the latest time below it, so lookups stay cheap.
+    #[cfg(feature = "cargo-last-use")]
+    fn from_entries(cargo_home: &Path, entries: Vec<(PathBuf, SystemTime)>) -> Self {
+        let mut folders: HashMap<PathBuf, SystemTime> = HashMap::new();
+        for (path, when) in &entries {
+            for folder in path.ancestors().skip(1) {
+                if folder == cargo_home || !folder.starts_with(cargo_home) {
+                    break;
+                }
+                let latest = folders.entry(folder.to_path_buf()).or_insert(*when);
+                *latest = (*latest).max(*when);
+            }
+        }
+        // an entry's own time wins over the entries below it
+        folders.extend(entries);
+        Self { times: folders }
+    }
+
+    /// When cargo last used `path`: its own entry, or for a folder holding
+    /// tracked entries (`registry/src/<index>`) the latest of them.
+    pub fn of(&self, path: &Path) -> Option<SystemTime> {
+        self.times.get(path).copied()
+    }
+
+    /// Replace the `newest_mtime` of every cache cargo tracks.
+    pub fn apply(&self, caches: &mut [Cache]) {
+        for cache in caches {
+            if let Some(when) = self.of(&cache.path) {
+                cache.newest_mtime = Some(when);
+            }
+        }
+    }
+}
+
+/// `$CARGO_HOME`, or `~/.cargo` when unset.
+pub fn cargo_home(home: &Path, env: impl Fn(&str) -> Option<String>) -> PathBuf {
+    env("CARGO_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".cargo"))
+}
+
+#[cfg(all(test, feature = "cargo-last-use"))]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86_400;
+
+    /// A tracker with cargo's schema: serde in the cache and in src, an
+    /// older `log` source, and one git checkout.
+    fn fixture(cargo_home: &Path) -> PathBuf {
+        let db = cargo_home.join(DB_FILE);
+        let conn = Connection::open(&db).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE registry_index (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 name TEXT UNIQUE NOT NULL,
+                 timestamp INTEGER NOT NULL);
+             CREATE TABLE registry_crate (
+                 registry_id INTEGER NOT NULL, name TEXT NOT NULL,
+                 size INTEGER NOT NULL, timestamp INTEGER NOT NULL,
+                 PRIMARY KEY (registry_id, name));
+             CREATE TABLE registry_src (
+                 registry_id INTEGER NOT NULL, name TEXT NOT NULL,
+                 size INTEGER, timestamp INTEGER NOT NULL,
+                 PRIMARY KEY (registry_id, name));
+             CREATE TABLE git_db (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 name TEXT UNIQUE NOT NULL,
+                 timestamp INTEGER NOT NULL);
+             CREATE TABLE git_checkout (
+                 git_id INTEGER NOT NULL, name TEXT UNIQUE NOT NULL,
+                 size INTEGER, timestamp INTEGER NOT NULL,
+                 PRIMARY KEY (git_id, name));
+             INSERT INTO registry_index VALUES (1, 'index.crates.io-1949cf8c6b5b557f', 864000);
+             INSERT INTO registry_crate VALUES (1, 'serde-1.0.200.crate', 10, 864000);
+             INSERT INTO registry_src VALUES (1, 'serde-1.0.200', 100, 777600);
+             INSERT INTO registry_src VALUES (1, 'log-0.4.21', 100, 86400);
+             INSERT INTO git_db VALUES (1, 'putzen-rs-0123456789abcdef', 432000);
+             INSERT INTO git_checkout VALUES (1, 'a1b2c3d', 50, 432000);",
+        )
+        .unwrap();
+        db
+    }
+
+    fn days(n: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(n * DAY)
+    }
+
+    #[test]
+    fn maps_every_table_to_its_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path();
+        let last_use = LastUse::open(&fixture(home), home).unwrap();
+        let src = home.join("registry/src/index.crates.io-1949cf8c6b5b557f");
+        assert_eq!(last_use.of(&src.join("serde-1.0.200")), Some(days(9)));
+        assert_eq!(last_use.of(&src.join("log-0.4.21")), Some(days(1)));
+        assert_eq!(
+            last_use
+                .of(&home
+                    .join("registry/cache/index.crates.io-1949cf8c6b5b557f/serde-1.0.200.crate")),
+            Some(days(10))
+        );
+        assert_eq!(
+            last_use.of(&home.join("git/checkouts/putzen-rs-0123456789abcdef/a1b2c3d")),
+            Some(days(5))
+        );
+        assert_eq!(last_use.of(&src.join("rand-0.8.5")), None);
+    }
+
+    #[test]
+    fn folders_take_the_latest_use_below_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path();
+        let last_use = LastUse::open(&fixture(home), home).unwrap();
+        assert_eq!(
+            last_use.of(&home.join("registry/src/index.crates.io-1949cf8c6b5b557f")),
+            Some(days(9))
+        );
+        assert_eq!(last_use.of(&home.join("registry")), Some(days(10)));
+        assert_eq!(last_use.of(&home.join("bin")), None, "not tracked by cargo");
+    }
+
+    #[test]
+    fn apply_overrides_mtimes_of_tracked_rows_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path();
+        let last_use = LastUse::open(&fixture(home), home).unwrap();
+        let src = home.join("registry/src/index.crates.io-1949cf8c6b5b557f");
+        let row = |name: &str| Cache {
+            label: name.into(),
+            path: src.join(name),
+            size_bytes: 1,
+            newest_mtime: Some(days(0)),
+            file_count: 1,
+            dir_count: 0,
+            top_files: Vec::new(),
+            unreadable: 0,
+            members: Vec::new(),
+        };
+        let mut rows = [row("serde-1.0.200"), row("rand-0.8.5")];
+        last_use.apply(&mut rows);
+        assert_eq!(rows[0].newest_mtime, Some(days(9)));
+        assert_eq!(rows[1].newest_mtime, Some(days(0)));
+    }
+
+    #[test]
+    fn load_without_tracker_is_none() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(LastUse::load(dir.path()).is_none());
+        fixture(dir.path());
+        assert!(LastUse::load(dir.path()).is_some());
+    }
+
+    #[test]
+    fn cargo_home_follows_the_variable() {
+        let home = Path::new("/u/sven");
+        assert_eq!(cargo_home(home, |_| None), PathBuf::from("/u/sven/.cargo"));
+        let env = |var: &str| (var == "CARGO_HOME").then(|| "/opt/cargo".to_string());
+        assert_eq!(cargo_home(home, env), PathBuf::from("/opt/cargo"));
+    }
+}
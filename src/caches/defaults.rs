@@ -2,19 +2,35 @@
 //!
 //! To add a cache directory:
 //!   1. Add one `///` line above a HOME-relative (or absolute) path literal.
-//!   2. Place it in the right block — see contribution rules in the spec.
-//!   3. Open a PR.
+//!   2. Follow it with `=> <Risk> "note"`: what deleting it costs, in a few
+//!      words, e.g. `=> Low "safe, npm re-downloads packages"`.
+//!   3. Place it in the right block — see contribution rules in the spec.
+//!   4. Open a PR.
 //!
 //! The `///` is the runtime label *and* the contributor-facing doc.
+//!
+//! When the tool lets users move its cache with an environment variable,
+//! name it after the path: `".cargo/registry/cache" env CARGO_HOME / "registry/cache"`
+//! scans `$CARGO_HOME/registry/cache` when `CARGO_HOME` is set. The suffix
+//! is optional for variables that point straight at the cache.
+
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
 
 #[macro_export]
 macro_rules! roots {
     //—— base case
     (@build [$($acc:tt)*]) => { &[ $($acc)* ] };
 
+    //—— optional fragment to `Option`
+    (@opt) => { None };
+    (@opt $value:expr) => { Some($value) };
+
     //—— ERROR: two or more `///` lines on the same entry
     (@build [$($acc:tt)*]
-        #[doc = $_a:literal] #[doc = $_b:literal] $path:literal $(, $($rest:tt)*)?
+        #[doc = $_a:literal] #[doc = $_b:literal] $path:literal
+        $(env $_env:ident $(/ $_suffix:literal)?)?
+        $(=> $_risk:ident $_note:literal)? $(, $($rest:tt)*)?
     ) => {
         compile_error!(concat!(
             "putzen: cache root \"", $path, "\" has multiple `///` lines. ",
@@ -24,19 +40,39 @@ macro_rules! roots {
 
     //—— happy path
     (@build [$($acc:tt)*]
-        #[doc = $label:literal] $path:literal $(, $($rest:tt)*)?
+        #[doc = $label:literal] $path:literal
+        $(env $env:ident $(/ $suffix:literal)?)?
+        => $risk:ident $note:literal $(, $($rest:tt)*)?
     ) => {
         roots!(@build [
             $($acc)*
             $crate::caches::defaults::DefaultRoot {
                 label: $crate::caches::defaults::strip_leading_spaces($label),
                 path:  $path,
+                env: roots!(@opt $(stringify!($env))?),
+                suffix: roots!(@opt $($($suffix)?)?),
+                risk: $crate::caches::defaults::Risk::$risk,
+                note: $note,
             },
         ] $($($rest)*)?)
     };
 
+    //—— ERROR: no risk tier and note
+    (@build [$($acc:tt)*]
+        #[doc = $_label:literal] $path:literal
+        $(env $_env:ident $(/ $_suffix:literal)?)? $(, $($rest:tt)*)?
+    ) => {
+        compile_error!(concat!(
+            "putzen: cache root \"", $path, "\" is missing its risk tier and note. ",
+            "Add `=> Low \"what deleting it costs\"` (or Medium / High) after the path."
+        ));
+    };
+
     //—— ERROR: path literal without preceding `///`
-    (@build [$($acc:tt)*] $path:literal $(, $($rest:tt)*)?) => {
+    (@build [$($acc:tt)*]
+        $path:literal $(env $_env:ident $(/ $_suffix:literal)?)?
+        $(=> $_risk:ident $_note:literal)? $(, $($rest:tt)*)?
+    ) => {
         compile_error!(concat!(
             "putzen: cache root \"", $path, "\" is missing its `///` label. ",
             "Add a one-line `///` doc comment above this entry."
@@ -47,9 +83,38 @@ macro_rules! roots {
     ( $($t:tt)* ) => { roots!(@build [] $($t)*) };
 }
 
+/// What deleting a cache costs the user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Risk {
+    /// Refetched or rebuilt on its own, at most a slower next build.
+    Low,
+    /// Comes back, but slowly: large downloads or a full re-resolve.
+    Medium,
+    /// Offline builds break or something is lost for good.
+    High,
+}
+
+impl Display for Risk {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Risk::Low => "low risk",
+            Risk::Medium => "medium risk",
+            Risk::High => "high risk",
+        })
+    }
+}
+
 pub struct DefaultRoot {
     pub label: &'static str,
     pub path: &'static str, // HOME-relative unless it begins with '/'
+    /// Environment variable that relocates the cache, e.g. `CARGO_HOME`.
+    pub env: Option<&'static str>,
+    /// Where the cache lives below `$env`; `None` when `$env` is the cache.
+    pub suffix: Option<&'static str>,
+    pub risk: Risk,
+    /// One line on what deleting it means, shown in the details pane.
+    pub note: &'static str,
 }
 
 /// Strip leading ASCII spaces from a `///` doc string at compile time.
@@ -89,108 +154,190 @@ pub const fn _missing_doc_check() {}
 /// ```
 pub const fn _multi_doc_check() {}
 
+/// An env override still needs its `///` label.
+///
+/// ```compile_fail
+/// const _: &[putzen_cli::caches::defaults::DefaultRoot] = putzen_cli::roots![
+///     ".a" env A_HOME / "cache",
+/// ];
+/// ```
+pub const fn _missing_doc_with_env_check() {}
+
+/// `roots!` requires a risk tier and note on every entry.
+///
+/// ```compile_fail
+/// const _: &[putzen_cli::caches::defaults::DefaultRoot] = putzen_cli::roots![
+///     /// a
+///     ".a",
+/// ];
+/// ```
+pub const fn _missing_risk_check() {}
+
+/// The risk tier must be one of `Low`, `Medium` or `High`.
+///
+/// ```compile_fail
+/// const _: &[putzen_cli::caches::defaults::DefaultRoot] = putzen_cli::roots![
+///     /// a
+///     ".a" => Harmless "safe",
+/// ];
+/// ```
+pub const fn _unknown_risk_check() {}
+
 // Cross-platform cache paths (resolve via ~ on every OS).
 pub const SEEDS: &[DefaultRoot] = roots![
     // ── Rust ────────────────────────────────────────────────────────
     /// cargo packaged crates
-    ".cargo/registry/cache",
+    ".cargo/registry/cache" env CARGO_HOME / "registry/cache"
+        => Low "safe, cargo re-downloads crates when needed",
     /// cargo extracted sources
-    ".cargo/registry/src",
+    ".cargo/registry/src" env CARGO_HOME / "registry/src"
+        => Low "safe, re-extracted from the packaged crates",
     /// cargo registry index
-    ".cargo/registry/index",
+    ".cargo/registry/index" env CARGO_HOME / "registry/index"
+        => Low "safe, refetched on the next build",
     /// cargo git checkouts
-    ".cargo/git/checkouts",
+    ".cargo/git/checkouts" env CARGO_HOME / "git/checkouts"
+        => Low "safe, checked out again from the git db",
     /// cargo git bare repos
-    ".cargo/git/db",
+    ".cargo/git/db" env CARGO_HOME / "git/db"
+        => Medium "git dependencies are cloned again",
     // ── Go / JVM / .NET package caches ──────────────────────────────
     /// go modules
-    "go/pkg/mod",
+    "go/pkg/mod" env GOMODCACHE
+        => Medium "re-downloaded by the next go build, offline builds break",
     /// maven local repo
-    ".m2/repository",
+    ".m2/repository"
+        => High "offline builds break, `mvn install`ed artifacts are lost",
     /// gradle caches
-    ".gradle/caches",
+    ".gradle/caches" env GRADLE_USER_HOME / "caches"
+        => Medium "slow to re-download, offline builds break",
     /// gradle wrapper distributions
-    ".gradle/wrapper/dists",
+    ".gradle/wrapper/dists" env GRADLE_USER_HOME / "wrapper/dists"
+        => Low "safe, the wrapper re-downloads gradle",
     /// ivy cache
-    ".ivy2/cache",
+    ".ivy2/cache"
+        => Medium "re-resolved on the next build, offline builds break",
     /// sbt boot
-    ".sbt/boot",
+    ".sbt/boot"
+        => Low "safe, sbt re-downloads itself",
     /// NuGet global packages
-    ".nuget/packages",
+    ".nuget/packages"
+        => Medium "restored again by the next dotnet restore",
     // ── Other language pkg caches ───────────────────────────────────
     /// Hex (Elixir) packages
-    ".hex/packages",
+    ".hex/packages"
+        => Low "safe, mix deps.get fetches them again",
     /// opam download cache
-    ".opam/download-cache",
+    ".opam/download-cache"
+        => Low "safe, only speeds up reinstalls",
     /// Clojure gitlibs
-    ".gitlibs",
+    ".gitlibs"
+        => Medium "git dependencies are cloned again",
     /// Cabal packages (Haskell)
-    ".cabal/packages",
+    ".cabal/packages"
+        => Medium "re-downloaded by the next cabal build",
     // ── ML / LLM model caches ───────────────────────────────────────
     /// Ollama models
-    ".ollama/models",
+    ".ollama/models" env OLLAMA_MODELS
+        => Medium "slow to re-download (GBs), ollama pull brings them back",
     /// triton compile cache
-    ".triton/cache",
+    ".triton/cache"
+        => Low "safe, kernels are recompiled on first use",
     /// CUDA NVRTC compute cache
-    ".nv/ComputeCache",
+    ".nv/ComputeCache"
+        => Low "safe, CUDA recompiles kernels on first use",
 ];
 
 #[cfg(target_family = "unix")]
 pub const SEEDS_OS: &[DefaultRoot] = roots![
     /// XDG cache home
-    ".cache",
+    ".cache" env XDG_CACHE_HOME
+        => Medium "mixed per-app caches, apps rebuild them",
     /// macOS per-app caches
-    "Library/Caches",
+    "Library/Caches"
+        => Medium "mixed per-app caches, apps rebuild them",
     /// Xcode DerivedData
-    "Library/Developer/Xcode/DerivedData",
+    "Library/Developer/Xcode/DerivedData"
+        => Low "safe, Xcode rebuilds it on the next build",
     /// Xcode Archives
-    "Library/Developer/Xcode/Archives",
+    "Library/Developer/Xcode/Archives"
+        => High "shipped builds and their dSYMs are lost",
     /// iOS DeviceSupport
-    "Library/Developer/Xcode/iOS DeviceSupport",
+    "Library/Developer/Xcode/iOS DeviceSupport"
+        => Medium "copied again when the device is next connected",
     /// CoreSimulator caches
-    "Library/Developer/CoreSimulator/Caches",
+    "Library/Developer/CoreSimulator/Caches"
+        => Low "safe, the simulator rebuilds it",
     /// npm
-    ".npm",
+    ".npm" env npm_config_cache
+        => Low "safe, npm re-downloads packages",
     /// yarn cache
-    ".yarn/cache",
+    ".yarn/cache"
+        => Low "safe, yarn re-downloads packages",
     /// bun install cache
-    ".bun/install/cache",
+    ".bun/install/cache"
+        => Low "safe, bun re-downloads packages",
     /// pnpm store (legacy dotfile path)
-    ".pnpm-store",
+    ".pnpm-store"
+        => Medium "projects linked to the store need pnpm install again",
+    /// pip cache (Linux XDG)
+    ".cache/pip" env PIP_CACHE_DIR
+        => Low "safe, pip re-downloads wheels",
+    /// uv cache (Linux XDG)
+    ".cache/uv" env UV_CACHE_DIR
+        => Low "safe, uv re-downloads packages",
+    /// HuggingFace hub (Linux XDG)
+    ".cache/huggingface" env HF_HOME
+        => Medium "slow to re-download (GBs)",
     /// sccache (Linux XDG)
-    ".cache/sccache",
+    ".cache/sccache" env SCCACHE_DIR
+        => Low "safe, the next builds are slower",
     /// sccache (macOS, via Mozilla `directories` crate)
-    "Library/Caches/Mozilla.sccache",
+    "Library/Caches/Mozilla.sccache" env SCCACHE_DIR
+        => Low "safe, the next builds are slower",
 ];
 
 #[cfg(target_family = "windows")]
 pub const SEEDS_OS: &[DefaultRoot] = roots![
     /// WinINet shared cache
-    "AppData/Local/Microsoft/Windows/INetCache",
+    "AppData/Local/Microsoft/Windows/INetCache"
+        => Low "safe, browser cache",
     /// Edge browser cache
-    "AppData/Local/Microsoft/Edge/User Data/Default/Cache",
+    "AppData/Local/Microsoft/Edge/User Data/Default/Cache"
+        => Low "safe, browser cache",
     /// Chrome browser cache
-    "AppData/Local/Google/Chrome/User Data/Default/Cache",
+    "AppData/Local/Google/Chrome/User Data/Default/Cache"
+        => Low "safe, browser cache",
     /// npm
-    "AppData/Roaming/npm-cache",
+    "AppData/Roaming/npm-cache" env npm_config_cache
+        => Low "safe, npm re-downloads packages",
     /// yarn
-    "AppData/Local/Yarn/Cache",
+    "AppData/Local/Yarn/Cache"
+        => Low "safe, yarn re-downloads packages",
     /// pnpm store
-    "AppData/Local/pnpm",
+    "AppData/Local/pnpm"
+        => Medium "projects linked to the store need pnpm install again",
     /// pip wheel cache
-    "AppData/Local/pip/Cache",
+    "AppData/Local/pip/Cache" env PIP_CACHE_DIR
+        => Low "safe, pip re-downloads wheels",
     /// uv cache
-    "AppData/Local/uv/cache",
+    "AppData/Local/uv/cache" env UV_CACHE_DIR
+        => Low "safe, uv re-downloads packages",
     /// HuggingFace hub
-    "AppData/Local/huggingface",
+    "AppData/Local/huggingface" env HF_HOME
+        => Medium "slow to re-download (GBs)",
     /// go build cache
-    "AppData/Local/go-build",
+    "AppData/Local/go-build"
+        => Low "safe, go rebuilds it",
     /// JetBrains caches
-    "AppData/Local/JetBrains",
+    "AppData/Local/JetBrains"
+        => Medium "IDEs re-index every project on start",
     /// VSCode CachedData
-    "AppData/Roaming/Code/CachedData",
+    "AppData/Roaming/Code/CachedData"
+        => Low "safe, VSCode rebuilds it",
     /// sccache
-    "AppData/Local/Mozilla/sccache",
+    "AppData/Local/Mozilla/sccache" env SCCACHE_DIR
+        => Low "safe, the next builds are slower",
 ];
 
 pub fn defaults() -> impl Iterator<Item = &'static DefaultRoot> {
@@ -205,7 +352,7 @@ mod tests {
     fn macro_emits_label_and_path() {
         const SAMPLE: &[DefaultRoot] = roots![
             /// cargo registry
-            ".cargo/registry/cache",
+            ".cargo/registry/cache" => Low "safe",
         ];
         assert_eq!(SAMPLE.len(), 1);
         assert_eq!(SAMPLE[0].label, "cargo registry");
@@ -216,11 +363,11 @@ mod tests {
     fn macro_emits_multiple_entries() {
         const SAMPLE: &[DefaultRoot] = roots![
             /// alpha
-            ".a",
+            ".a" => Low "safe",
             /// beta
-            ".b",
+            ".b" => Low "safe",
             /// gamma
-            ".c",
+            ".c" => Low "safe",
         ];
         let labels: Vec<_> = SAMPLE.iter().map(|r| r.label).collect();
         let paths: Vec<_> = SAMPLE.iter().map(|r| r.path).collect();
@@ -228,11 +375,43 @@ mod tests {
         assert_eq!(paths, [".a", ".b", ".c"]);
     }
 
+    #[test]
+    fn macro_emits_env_and_suffix() {
+        const SAMPLE: &[DefaultRoot] = roots![
+            /// plain
+            ".a" => Low "safe",
+            /// env only
+            ".b" env B_HOME => Low "safe",
+            /// env and suffix
+            ".c/cache" env C_HOME / "cache" => Low "safe",
+        ];
+        assert_eq!((SAMPLE[0].env, SAMPLE[0].suffix), (None, None));
+        assert_eq!((SAMPLE[1].env, SAMPLE[1].suffix), (Some("B_HOME"), None));
+        assert_eq!(
+            (SAMPLE[2].env, SAMPLE[2].suffix),
+            (Some("C_HOME"), Some("cache"))
+        );
+    }
+
+    #[test]
+    fn macro_emits_risk_and_note() {
+        const SAMPLE: &[DefaultRoot] = roots![
+            /// maven
+            ".m2/repository" => High "offline builds break",
+            /// gradle
+            ".gradle/caches" env GRADLE_USER_HOME / "caches" => Medium "slow",
+        ];
+        assert_eq!(SAMPLE[0].risk, Risk::High);
+        assert_eq!(SAMPLE[0].note, "offline builds break");
+        assert_eq!(SAMPLE[1].risk, Risk::Medium);
+        assert_eq!(SAMPLE[1].suffix, Some("caches"));
+    }
+
     #[test]
     fn label_has_no_leading_space() {
         const SAMPLE: &[DefaultRoot] = roots![
             /// no leading space
-            ".x",
+            ".x" => Low "safe",
         ];
         assert_eq!(SAMPLE[0].label, "no leading space");
         assert!(!SAMPLE[0].label.starts_with(' '));
@@ -250,6 +429,13 @@ mod tests {
         }
     }
 
+    #[test]
+    fn defaults_have_notes() {
+        for r in defaults() {
+            assert!(!r.note.is_empty(), "empty note for {}", r.path);
+        }
+    }
+
     #[test]
     fn defaults_paths_are_unique() {
         let mut seen = std::collections::HashSet::new();
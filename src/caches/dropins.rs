@@ -0,0 +1,182 @@
+//! Drop-in cache catalogue files in `~/.config/putzen/caches.d/*.toml`.
+//!
+//! ```toml
+//! label = "bazel disk cache"
+//! path = ".cache/bazel-disk"   # HOME-relative unless it begins with '/'
+//! tool = "bazel"               # optional
+//! notes = "safe to drop, bazel refills it on the next build"  # optional
+//! env = "BAZEL_DISK_CACHE"     # optional, relocates the cache when set
+//! risk = "low"                 # optional: low, medium or high
+//! ```
+//!
+//! Every file adds one entry to the built-in [`defaults`](super::defaults::defaults);
+//! a drop-in with the label of a built-in entry replaces it.
+
+use super::defaults::{DefaultRoot, Risk};
+use serde::Deserialize;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// One entry of the seed catalogue, built in or from a drop-in file.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Seed {
+    pub label: String,
+    /// HOME-relative unless it begins with '/'.
+    pub path: String,
+    /// The tool that fills this cache, e.g. `bazel`.
+    #[serde(default)]
+    pub tool: Option<String>,
+    /// What deleting it costs, shown in the details pane.
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub risk: Option<Risk>,
+    /// Environment variable that relocates the cache, see [`DefaultRoot::env`].
+    #[serde(default)]
+    pub env: Option<String>,
+    /// Where the cache lives below `$env`.
+    #[serde(default)]
+    pub suffix: Option<String>,
+}
+
+impl From<&DefaultRoot> for Seed {
+    fn from(root: &DefaultRoot) -> Self {
+        Self {
+            label: root.label.to_string(),
+            path: root.path.to_string(),
+            tool: None,
+            notes: Some(root.note.to_string()),
+            risk: Some(root.risk),
+            env: root.env.map(String::from),
+            suffix: root.suffix.map(String::from),
+        }
+    }
+}
+
+/// `caches.d` next to the config file, so `$PUTZEN_CONFIG` moves both.
+pub fn dir() -> Option<PathBuf> {
+    crate::config::config_path()
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+        .map(|d| d.join("caches.d"))
+}
+
+/// Parse every `*.toml` in `dir`, in file name order. A missing directory
+/// holds no drop-ins; a malformed file is an error naming the file.
+pub fn load(dir: &Path) -> io::Result<Vec<Seed>> {
+    let mut files: Vec<PathBuf> = match std::fs::read_dir(dir) {
+        Ok(read) => read
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+            .collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    files.sort();
+    files
+        .iter()
+        .map(|file| {
+            let text = std::fs::read_to_string(file)?;
+            toml::from_str(&text).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {e}", file.display()),
+                )
+            })
+        })
+        .collect()
+}
+
+/// The built-in defaults with `drop_ins` merged in: a drop-in replaces the
+/// built-in entry of the same label, anything else is appended.
+pub fn catalogue(drop_ins: Vec<Seed>) -> Vec<Seed> {
+    let mut seeds: Vec<Seed> = super::defaults::defaults().map(Seed::from).collect();
+    for drop_in in drop_ins {
+        match seeds.iter_mut().find(|s| s.label == drop_in.label) {
+            Some(seed) => *seed = drop_in,
+            None => seeds.push(drop_in),
+        }
+    }
+    seeds
+}
+
+/// [`catalogue`] with the drop-ins of the user's config directory.
+pub fn load_catalogue() -> io::Result<Vec<Seed>> {
+    let drop_ins = match dir() {
+        Some(dir) => load(&dir)?,
+        None => Vec::new(),
+    };
+    Ok(catalogue(drop_ins))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_toml_files_in_name_order() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("b.toml"),
+            "label = \"bazel\"\npath = \".cache/bazel\"\ntool = \"bazel\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("a.toml"),
+            "label = \"ccache\"\npath = \"/var/ccache\"\nnotes = \"shared\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.md"), "not a drop-in").unwrap();
+
+        let seeds = load(dir.path()).unwrap();
+        assert_eq!(seeds.len(), 2);
+        assert_eq!(seeds[0].label, "ccache");
+        assert_eq!(seeds[0].notes.as_deref(), Some("shared"));
+        assert_eq!(seeds[1].tool.as_deref(), Some("bazel"));
+    }
+
+    #[test]
+    fn missing_dir_has_no_drop_ins() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load(&dir.path().join("caches.d")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed_file_names_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("bad.toml"), "label = \"x\"\n").unwrap();
+        let err = load(dir.path()).unwrap_err();
+        assert!(err.to_string().contains("bad.toml"), "{err}");
+    }
+
+    #[test]
+    fn drop_ins_extend_and_replace_defaults() {
+        let builtin = super::super::defaults::defaults().count();
+        let seeds = catalogue(vec![
+            Seed {
+                label: "npm".into(),
+                path: "/srv/npm".into(),
+                tool: None,
+                notes: None,
+                risk: None,
+                env: None,
+                suffix: None,
+            },
+            Seed {
+                label: "bazel".into(),
+                path: ".cache/bazel".into(),
+                tool: None,
+                notes: None,
+                risk: None,
+                env: None,
+                suffix: None,
+            },
+        ]);
+        let npm: Vec<_> = seeds.iter().filter(|s| s.label == "npm").collect();
+        assert_eq!(npm.len(), 1);
+        assert_eq!(npm[0].path, "/srv/npm");
+        assert_eq!(seeds.last().unwrap().label, "bazel");
+        assert_eq!(seeds.len(), builtin + 1);
+    }
+}
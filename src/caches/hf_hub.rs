@@ -0,0 +1,314 @@
+//! Hugging Face hub cache analyzer.
+//!
+//! `~/.cache/huggingface/hub` keeps one folder per repo,
+//! `models--<org>--<name>` (or `datasets--…`, `spaces--…`), holding
+//!
+//! - `blobs/<hash>`: the file contents,
+//! - `snapshots/<commit>/<file>`: symlinks into `blobs`, one tree per revision,
+//! - `refs/<branch>`: the commit a branch or tag points at.
+//!
+//! Drilling into the hub shows the repos as `org/name`; drilling into a repo
+//! shows one row per revision, flagged stale when no ref points at it, plus
+//! an "orphaned blobs" row for blobs no snapshot links to. Deleting a
+//! revision removes its snapshot, the refs pointing at it and the blobs only
+//! it uses.
+
+use super::model::{Cache, ORPHANED};
+use super::scan::{read_names, stat_file};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// `org/name` for a repo folder name, prefixed with `datasets/` or `spaces/`
+/// like the hub's URLs. `None` for anything else.
+pub fn repo_label(dir_name: &str) -> Option<String> {
+    let (kind, repo) = dir_name.split_once("--")?;
+    let prefix = match kind {
+        "models" => "",
+        "datasets" => "datasets/",
+        "spaces" => "spaces/",
+        _ => return None,
+    };
+    Some(format!("{prefix}{}", repo.replace("--", "/")))
+}
+
+fn is_repo(dir: &Path) -> bool {
+    dir.file_name()
+        .and_then(|n| n.to_str())
+        .and_then(repo_label)
+        .is_some()
+        && dir.join("snapshots").is_dir()
+}
+
+/// Name the rows of repo folders `org/name`.
+pub fn relabel(caches: &mut [Cache]) {
+    for cache in caches {
+        if !is_repo(&cache.path) {
+            continue;
+        }
+        if let Some(label) = cache
+            .path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(repo_label)
+        {
+            cache.label = label;
+        }
+    }
+}
+
+/// The revision rows and the orphaned blobs row of the repo folder `dir`,
+/// `None` when `dir` is not a repo folder.
+pub fn repo_rows(dir: &Path) -> Option<Vec<Cache>> {
+    if !is_repo(dir) {
+        return None;
+    }
+    let blobs = read_blobs(&dir.join("blobs"));
+    let refs = read_refs(&dir.join("refs"));
+    let snapshots: Vec<(String, Vec<(String, String)>)> = read_names(&dir.join("snapshots"))
+        .into_iter()
+        .map(|rev| {
+            let links = snapshot_links(&dir.join("snapshots").join(&rev));
+            (rev, links)
+        })
+        .collect();
+
+    // How many revisions link to each blob.
+    let mut users: HashMap<&str, usize> = HashMap::new();
+    for (_, links) in &snapshots {
+        let mut seen: Vec<&str> = links.iter().map(|(_, blob)| blob.as_str()).collect();
+        seen.sort_unstable();
+        seen.dedup();
+        for blob in seen {
+            *users.entry(blob).or_default() += 1;
+        }
+    }
+
+    let mut rows = Vec::new();
+    for (rev, links) in &snapshots {
+        let snapshot = dir.join("snapshots").join(rev);
+        let mut snapshot_row = super::scan::stat_dir(&snapshot);
+        snapshot_row.label = format!("snapshots/{rev}");
+        let mut members = vec![snapshot_row];
+        let mut taken: Vec<&str> = Vec::new();
+        for (file, blob) in links {
+            if users.get(blob.as_str()) != Some(&1) || taken.contains(&blob.as_str()) {
+                continue;
+            }
+            taken.push(blob);
+            if let Some(cache) = blobs.get(blob.as_str()) {
+                members.push(Cache {
+                    label: file.clone(),
+                    ..cache.clone()
+                });
+            }
+        }
+        let names = refs.get(rev.as_str());
+        for name in names.into_iter().flatten() {
+            members.extend(stat_file(
+                &dir.join("refs").join(name),
+                format!("refs/{name}"),
+            ));
+        }
+        let short: String = rev.chars().take(8).collect();
+        let label = match names {
+            Some(names) => format!("{short} ({})", names.join(", ")),
+            None => format!("{short} (stale)"),
+        };
+        rows.push(Cache::virtual_row(label, snapshot, members));
+    }
+
+    let mut orphans: Vec<Cache> = blobs
+        .into_iter()
+        .filter(|(hash, _)| !users.contains_key(hash.as_str()))
+        .map(|(_, cache)| cache)
+        .collect();
+    orphans.sort_by(|a, b| a.label.cmp(&b.label));
+    if !orphans.is_empty() {
+        rows.push(Cache::virtual_row(
+            ORPHANED.to_string(),
+            dir.join(ORPHANED),
+            orphans,
+        ));
+    }
+    Some(rows)
+}
+
+/// Every blob by hash, labelled with its hash. `<hash>.incomplete` is a
+/// download in progress, not an orphan.
+fn read_blobs(dir: &Path) -> HashMap<String, Cache> {
+    read_names(dir)
+        .into_iter()
+        .filter(|hash| !hash.ends_with(".incomplete"))
+        .filter_map(|hash| {
+            let cache = stat_file(&dir.join(&hash), hash.clone())?;
+            Some((hash, cache))
+        })
+        .collect()
+}
+
+/// Commit → names of the refs pointing at it. Refs may nest (`refs/pr/1`).
+fn read_refs(dir: &Path) -> HashMap<String, Vec<String>> {
+    let mut by_rev: HashMap<String, Vec<String>> = HashMap::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(rel) = pending.pop() {
+        for name in read_names(&dir.join(&rel)) {
+            let rel = rel.join(name);
+            let path = dir.join(&rel);
+            if path.is_dir() {
+                pending.push(rel);
+            } else if let Ok(rev) = std::fs::read_to_string(&path) {
+                let name = rel.to_string_lossy().to_string();
+                by_rev.entry(rev.trim().to_string()).or_default().push(name);
+            }
+        }
+    }
+    for names in by_rev.values_mut() {
+        names.sort();
+    }
+    by_rev
+}
+
+/// (file inside the snapshot, blob hash) for every symlink of a snapshot.
+fn snapshot_links(snapshot: &Path) -> Vec<(String, String)> {
+    let mut links = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(rel) = pending.pop() {
+        let Ok(read) = std::fs::read_dir(snapshot.join(&rel)) else {
+            continue;
+        };
+        for entry in read.flatten() {
+            let rel = rel.join(entry.file_name());
+            let Ok(kind) = entry.file_type() else {
+                continue;
+            };
+            if kind.is_dir() {
+                pending.push(rel);
+            } else if kind.is_symlink() {
+                let Ok(target) = std::fs::read_link(entry.path()) else {
+                    continue;
+                };
+                if let Some(blob) = target.file_name() {
+                    let blob = blob.to_string_lossy().to_string();
+                    links.push((rel.to_string_lossy().to_string(), blob));
+                }
+            }
+        }
+    }
+    links.sort();
+    links
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    /// `models--acme--tiny` with two revisions sharing `config.json`:
+    /// `aaaa…` is `main`, `bbbb…` is stale and has its own weights, one
+    /// blob nothing links to, and one still downloading.
+    fn hub() -> tempfile::TempDir {
+        let hub = tempfile::tempdir().unwrap();
+        let repo = hub.path().join("models--acme--tiny");
+        let blobs = repo.join("blobs");
+        std::fs::create_dir_all(&blobs).unwrap();
+        for (hash, size) in [
+            ("cfg", 10),
+            ("w1", 1_000),
+            ("w2", 2_000),
+            ("lost", 300),
+            ("w3.incomplete", 500),
+        ] {
+            std::fs::write(blobs.join(hash), vec![0u8; size]).unwrap();
+        }
+        std::fs::create_dir_all(repo.join("refs")).unwrap();
+        std::fs::write(repo.join("refs/main"), "aaaaaaaaaaaa").unwrap();
+        for (rev, weights) in [("aaaaaaaaaaaa", "w1"), ("bbbbbbbbbbbb", "w2")] {
+            let snap = repo.join("snapshots").join(rev);
+            std::fs::create_dir_all(snap.join("onnx")).unwrap();
+            symlink("../../blobs/cfg", snap.join("config.json")).unwrap();
+            symlink(
+                format!("../../../blobs/{weights}"),
+                snap.join("onnx/model.onnx"),
+            )
+            .unwrap();
+        }
+        std::fs::create_dir_all(hub.path().join("datasets--squad")).unwrap();
+        hub
+    }
+
+    #[test]
+    fn labels_repos_like_the_hub() {
+        assert_eq!(
+            repo_label("models--acme--tiny").as_deref(),
+            Some("acme/tiny")
+        );
+        assert_eq!(repo_label("models--gpt2").as_deref(), Some("gpt2"));
+        assert_eq!(
+            repo_label("datasets--acme--squad").as_deref(),
+            Some("datasets/acme/squad")
+        );
+        assert_eq!(repo_label(".locks"), None);
+        assert_eq!(repo_label("version--x"), None);
+    }
+
+    #[test]
+    fn relabel_only_touches_repo_folders() {
+        let hub = hub();
+        let mut rows = crate::caches::scan::enumerate_seed(hub.path());
+        rows.sort_by(|a, b| a.label.cmp(&b.label));
+        relabel(&mut rows);
+        let labels: Vec<_> = rows.iter().map(|r| r.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            ["datasets--squad", "acme/tiny"],
+            "no snapshots, no repo"
+        );
+    }
+
+    #[test]
+    fn revisions_hold_their_exclusive_blobs_and_refs() {
+        let hub = hub();
+        let repo = hub.path().join("models--acme--tiny");
+        let rows = repo_rows(&repo).unwrap();
+        let labels: Vec<_> = rows.iter().map(|r| r.label.as_str()).collect();
+        assert_eq!(labels, ["aaaaaaaa (main)", "bbbbbbbb (stale)", ORPHANED]);
+
+        let main = &rows[0];
+        assert_eq!(main.path, repo.join("snapshots/aaaaaaaaaaaa"));
+        let members: Vec<_> = main.members.iter().map(|m| m.label.as_str()).collect();
+        assert_eq!(
+            members,
+            ["snapshots/aaaaaaaaaaaa", "onnx/model.onnx", "refs/main"],
+            "config.json is shared with the stale revision"
+        );
+        assert_eq!(main.members[1].path, repo.join("blobs/w1"));
+
+        let stale = &rows[1];
+        assert!(stale
+            .members
+            .iter()
+            .any(|m| m.path == repo.join("blobs/w2")));
+        assert!(stale.size_bytes >= 2_000);
+
+        let orphaned = &rows[2];
+        assert_eq!(orphaned.size_bytes, 300, "downloads in progress stay");
+        assert_eq!(orphaned.members.len(), 1);
+        assert_eq!(orphaned.members[0].path, repo.join("blobs/lost"));
+    }
+
+    #[test]
+    fn shortens_non_ascii_revisions_by_characters() {
+        let hub = hub();
+        let repo = hub.path().join("models--acme--tiny");
+        std::fs::create_dir_all(repo.join("snapshots/aéééé")).unwrap();
+        let rows = repo_rows(&repo).unwrap();
+        assert!(rows.iter().any(|r| r.label == "aéééé (stale)"));
+    }
+
+    #[test]
+    fn not_a_repo_folder() {
+        let hub = hub();
+        assert!(repo_rows(hub.path()).is_none());
+        assert!(repo_rows(&hub.path().join("datasets--squad")).is_none());
+    }
+}
@@ -0,0 +1,345 @@
+//! Non-interactive `putzen caches`: `--list` prints the ranked caches,
+//! `--delete-older-than` removes the cold, big ones without the TUI.
+
+use super::format::{human_age, human_size, tildify};
+use super::model::{Cache, FloorPolicy};
+use super::scan::enclosing;
+use crate::cleaner::{Clean, DoCleanUp};
+use crate::report::rfc3339;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// `caches` in the order the TUI shows them on start (by score), so the
+/// table and the TUI never disagree on what comes first.
+pub fn ranked(caches: Vec<Cache>, floor: FloorPolicy, now: SystemTime) -> Vec<Cache> {
+    let mut state = super::initial_state("", floor, false, false);
+    state.now = now;
+    state.all = caches;
+    let order = state.sorted_indices();
+    let mut all: Vec<Option<Cache>> = state.all.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| all[i].take()).collect()
+}
+
+/// Which caches `--delete-older-than` / `--min-size` pick.
+#[derive(Clone, Copy, Debug)]
+pub struct Prune {
+    pub older_than: Duration,
+    pub min_size: u64,
+}
+
+impl Prune {
+    /// Empty and ACTIVE caches are never picked, whatever the thresholds.
+    pub fn matches(&self, cache: &Cache, floor: FloorPolicy, now: SystemTime) -> bool {
+        let Some(age) = cache.age(now) else {
+            return false;
+        };
+        age >= self.older_than && cache.size_bytes >= self.min_size && !floor.is_active(Some(age))
+    }
+}
+
+/// One cache as it shows up in `--json`.
+#[derive(Debug, Serialize)]
+pub struct CacheRow {
+    pub label: String,
+    pub path: String,
+    pub size_bytes: u64,
+    /// RFC 3339 mtime of the newest file inside, `None` when empty.
+    pub newest_mtime: Option<String>,
+    pub score: f64,
+    pub active: bool,
+    pub file_count: u64,
+}
+
+impl CacheRow {
+    pub fn new(cache: &Cache, floor: FloorPolicy, now: SystemTime) -> Self {
+        Self {
+            label: cache.label.clone(),
+            path: cache.path.display().to_string(),
+            size_bytes: cache.size_bytes,
+            newest_mtime: cache.newest_mtime.and_then(rfc3339),
+            score: cache.score(now),
+            active: floor.is_active(cache.age(now)),
+            file_count: cache.file_count,
+        }
+    }
+}
+
+/// The ranked table: rank, size, age since last use, ACTIVE flag and path.
+pub fn render_table(
+    caches: &[Cache],
+    floor: FloorPolicy,
+    now: SystemTime,
+    home: Option<&Path>,
+) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:>3}  {:>9}  {:>4}  {:6}  PATH",
+        "#", "SIZE", "AGE", ""
+    );
+    for (rank, cache) in caches.iter().enumerate() {
+        let age = cache.age(now);
+        let active = if floor.is_active(age) { "ACTIVE" } else { "" };
+        let _ = writeln!(
+            out,
+            "{:>3}  {:>9}  {:>4}  {active:6}  {}",
+            rank + 1,
+            human_size(cache.size_bytes),
+            age.map(human_age).unwrap_or_else(|| "-".into()),
+            tildify(&cache.path, home),
+        );
+    }
+    let total: u64 = caches.iter().map(|c| c.size_bytes).sum();
+    let _ = writeln!(
+        out,
+        "{} cache(s), {} in total",
+        caches.len(),
+        human_size(total)
+    );
+    out
+}
+
+/// What a `--delete-older-than` run did.
+#[derive(Debug, Default, Serialize)]
+pub struct PruneOutcome {
+    pub dry_run: bool,
+    pub deleted: Vec<CacheRow>,
+    pub failed: Vec<PruneFailure>,
+    /// Bytes removed, or that would be removed in a dry-run.
+    pub freed_bytes: u64,
+    /// Read-only folders made writable so a deletion could go through.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub fixed_permissions: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PruneFailure {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// Hand every cache to `cleaner`, the same way the TUI's delete effect does.
+/// A cache inside another one of `caches` goes with it. A row with members
+/// (a virtual row, or a folder around a nested seed) deletes its members
+/// instead of its path.
+pub fn delete(
+    caches: &[Cache],
+    cleaner: &dyn DoCleanUp,
+    dry_run: bool,
+    floor: FloorPolicy,
+    now: SystemTime,
+) -> PruneOutcome {
+    let mut outcome = PruneOutcome {
+        dry_run,
+        ..Default::default()
+    };
+    let items: Vec<(usize, &Path)> = caches
+        .iter()
+        .enumerate()
+        .flat_map(|(n, cache)| {
+            if cache.members.is_empty() {
+                vec![(n, cache.path.as_path())]
+            } else {
+                cache
+                    .members
+                    .iter()
+                    .map(|m| (n, m.path.as_path()))
+                    .collect()
+            }
+        })
+        .collect();
+    let paths: Vec<&Path> = items.iter().map(|(_, path)| *path).collect();
+    let outer: Vec<Option<usize>> = (0..items.len()).map(|i| enclosing(&paths, i)).collect();
+    let results: Vec<Option<Result<Clean, String>>> = items
+        .iter()
+        .zip(&outer)
+        .map(|((_, path), outer)| {
+            outer
+                .is_none()
+                .then(|| cleaner.do_cleanup(path).map_err(|e| e.to_string()))
+        })
+        .collect();
+    for (n, cache) in caches.iter().enumerate() {
+        let mut error = None;
+        for (i, _) in items.iter().enumerate().filter(|(_, (row, _))| *row == n) {
+            match results[outer[i].unwrap_or(i)].as_ref() {
+                Some(Ok(clean)) => {
+                    if let (None, Clean::CleanedAfterChmod(fixed)) = (outer[i], clean) {
+                        outcome.fixed_permissions += fixed;
+                    }
+                }
+                Some(Err(e)) => {
+                    error.get_or_insert_with(|| e.clone());
+                }
+                None => unreachable!("outermost caches are always cleaned"),
+            }
+        }
+        match error {
+            None => {
+                outcome.freed_bytes += cache.size_bytes;
+                outcome.deleted.push(CacheRow::new(cache, floor, now));
+            }
+            Some(error) => outcome.failed.push(PruneFailure {
+                path: cache.path.clone(),
+                error,
+            }),
+        }
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleaner::DryRunCleaner;
+
+    const DAY: u64 = 86_400;
+
+    fn now() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_000 * DAY)
+    }
+
+    fn floor() -> FloorPolicy {
+        FloorPolicy {
+            floor: Duration::from_secs(7 * DAY),
+        }
+    }
+
+    fn cache(name: &str, size_bytes: u64, age_days: Option<u64>) -> Cache {
+        Cache {
+            label: name.into(),
+            path: PathBuf::from("/u/sven/.cache").join(name),
+            size_bytes,
+            newest_mtime: age_days.map(|d| now() - Duration::from_secs(d * DAY)),
+            file_count: 1,
+            dir_count: 0,
+            top_files: Vec::new(),
+            unreadable: 0,
+            members: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn ranked_orders_by_score_like_the_tui() {
+        let caches = vec![
+            cache("small-old", 1 << 20, Some(100)),
+            cache("big-old", 1 << 30, Some(100)),
+            cache("empty", 0, None),
+            cache("big-fresh", 1 << 30, Some(1)),
+        ];
+        let labels: Vec<_> = ranked(caches, floor(), now())
+            .into_iter()
+            .map(|c| c.label)
+            .collect();
+        assert_eq!(labels, ["big-old", "big-fresh", "small-old", "empty"]);
+    }
+
+    #[test]
+    fn prune_needs_age_size_and_no_activity() {
+        let prune = Prune {
+            older_than: Duration::from_secs(90 * DAY),
+            min_size: 1 << 30,
+        };
+        assert!(prune.matches(&cache("a", 2 << 30, Some(100)), floor(), now()));
+        assert!(!prune.matches(&cache("b", 2 << 30, Some(30)), floor(), now()));
+        assert!(!prune.matches(&cache("c", 1 << 20, Some(100)), floor(), now()));
+        assert!(!prune.matches(&cache("d", 2 << 30, None), floor(), now()));
+
+        let everything = Prune {
+            older_than: Duration::ZERO,
+            min_size: 0,
+        };
+        assert!(
+            !everything.matches(&cache("e", 1, Some(1)), floor(), now()),
+            "ACTIVE caches are never pruned"
+        );
+    }
+
+    #[test]
+    fn table_lists_rank_size_age_and_flags_active() {
+        let caches = [
+            cache("npm", 3 << 30, Some(40)),
+            cache("pip", 1 << 20, Some(2)),
+        ];
+        let home = PathBuf::from("/u/sven");
+        let table = render_table(&caches, floor(), now(), Some(&home));
+        let lines: Vec<_> = table.lines().collect();
+        assert!(lines[1].contains("3.0 GiB") && lines[1].contains("1mo"));
+        assert!(lines[1].ends_with("~/.cache/npm"));
+        assert!(!lines[1].contains("ACTIVE"));
+        assert!(lines[2].contains("ACTIVE"));
+        assert_eq!(lines[3], "2 cache(s), 3.0 GiB in total");
+    }
+
+    #[test]
+    fn dry_run_delete_reports_what_would_be_freed() {
+        let caches = [cache("npm", 100, Some(40)), cache("pip", 20, Some(40))];
+        let outcome = delete(&caches, &DryRunCleaner, true, floor(), now());
+        assert_eq!(outcome.freed_bytes, 120);
+        assert_eq!(outcome.deleted.len(), 2);
+        let json = serde_json::to_value(&outcome).unwrap();
+        assert_eq!(json["dry_run"], true);
+        assert_eq!(json["deleted"][0]["label"], "npm");
+        assert!(json.get("fixed_permissions").is_none());
+    }
+
+    #[test]
+    fn nested_cache_goes_with_its_parent() {
+        struct Recording(std::cell::RefCell<Vec<PathBuf>>);
+        impl DoCleanUp for Recording {
+            fn do_cleanup(&self, path: &Path) -> std::io::Result<Clean> {
+                self.0.borrow_mut().push(path.to_path_buf());
+                Ok(Clean::Cleaned)
+            }
+        }
+        let mut inner = cache("ac", 1_000, Some(40));
+        inner.path = PathBuf::from("/u/sven/.cache/tools/bazel/ac");
+        let caches = [inner, cache("tools", 10, Some(40))];
+        let cleaner = Recording(Default::default());
+        let outcome = delete(&caches, &cleaner, false, floor(), now());
+        assert_eq!(
+            cleaner.0.into_inner(),
+            [PathBuf::from("/u/sven/.cache/tools")]
+        );
+        assert_eq!(outcome.deleted.len(), 2);
+        assert_eq!(outcome.freed_bytes, 1_010);
+    }
+
+    #[test]
+    fn row_around_a_nested_seed_prunes_by_its_own_age_and_spares_the_seed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("tools/bazel/disk/ac")).unwrap();
+        let old = root.join("tools/old");
+        std::fs::write(&old, [0u8; 10]).unwrap();
+        let long_ago = now() - Duration::from_secs(100 * DAY);
+        filetime::set_file_mtime(&old, filetime::FileTime::from_system_time(long_ago)).unwrap();
+        // the nested seed is in use right now
+        std::fs::write(root.join("tools/bazel/disk/ac/blob"), [0u8; 1_000]).unwrap();
+
+        let caches = crate::caches::scan::collect(&[root.clone(), root.join("tools/bazel/disk")]);
+        let tools: Vec<Cache> = caches.into_iter().filter(|c| c.label == "tools").collect();
+        let prune = Prune {
+            older_than: Duration::from_secs(30 * DAY),
+            min_size: 0,
+        };
+        assert!(prune.matches(&tools[0], floor(), SystemTime::now()));
+
+        let outcome = delete(
+            &tools,
+            &crate::cleaner::ProperCleaner,
+            false,
+            floor(),
+            now(),
+        );
+        assert_eq!(outcome.freed_bytes, 10);
+        assert!(!old.exists());
+        assert!(root.join("tools/bazel/disk/ac/blob").exists());
+    }
+}
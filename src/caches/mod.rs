@@ -1,8 +1,15 @@
-//! `putzen caches` — interactive cache cleanup TUI.
+//! `putzen caches` — interactive cache cleanup TUI, plus a non-interactive
+//! `--list` / `--delete-older-than` mode for scripts.
 
+pub mod cargo_gc;
+pub mod cargo_last_use;
 pub mod defaults;
+pub mod dropins;
 pub mod format;
+pub mod hf_hub;
+pub mod listing;
 pub mod model;
+pub mod ollama;
 pub mod scan;
 pub mod tui;
 
@@ -10,20 +17,34 @@ use std::io;
 use std::path::PathBuf;
 
 pub struct CachesArgs {
-    pub roots: Vec<PathBuf>,
+    pub seeds: SeedSelection,
     pub floor: Option<String>,
     pub dry_run: bool,
     pub yes: bool,
+    /// Print the ranked table instead of opening the TUI.
+    pub list: bool,
+    /// Machine output for `list` and `delete_older_than`.
+    pub json: bool,
+    /// Delete caches at least this cold without the TUI.
+    pub delete_older_than: Option<std::time::Duration>,
+    /// Only list or delete caches of at least this many bytes.
+    pub min_size: u64,
+    /// Projects whose `Cargo.lock` files keep registry entries alive.
+    pub project_roots: Vec<PathBuf>,
 }
 
 pub fn run(args: CachesArgs) -> io::Result<()> {
-    use std::time::{Duration, Instant, SystemTime};
+    use std::time::Duration;
 
     let home = std::env::var_os("HOME")
         .map(PathBuf::from)
         .ok_or_else(|| io::Error::other("HOME is not set"))?;
 
-    let seeds = select_seeds(&home, &args.roots);
+    let catalogue = dropins::load_catalogue()?;
+    let seeds = select_seeds(&home, &catalogue, &args.seeds, |var| {
+        std::env::var(var).ok()
+    })
+    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
 
     let floor = args
         .floor
@@ -32,25 +53,76 @@ pub fn run(args: CachesArgs) -> io::Result<()> {
         .transpose()
         .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
         .unwrap_or(Duration::from_secs(7 * 86_400));
+    let floor = model::FloorPolicy { floor };
+
+    if args.list || args.delete_older_than.is_some() {
+        return run_batch(&args, &home, &seeds, floor);
+    }
+    if wants_plain_text(
+        io::IsTerminal::is_terminal(&io::stdout()),
+        std::env::var("TERM").ok().as_deref(),
+    ) {
+        // Piped into `less` or a CI log: raw mode would fail, so print
+        // what the TUI would have opened with.
+        let args = CachesArgs { list: true, ..args };
+        return run_batch(&args, &home, &seeds, floor);
+    }
 
     // Start with an empty list + a visible spinner.  The actual seed scan
     // runs on a worker (Effect::LoadSeeds) so the TUI is responsive
     // immediately even when HOME contains huge cache trees.
-    let state = tui::State {
+    let mut state = initial_state("putzen caches — ranked", floor, args.dry_run, args.yes);
+    state.seeds = seed_infos(&home, &catalogue, |var| std::env::var(var).ok());
+    state.cargo_last_use =
+        cargo_last_use::LastUse::load(&cargo_last_use::cargo_home(&home, |var| {
+            std::env::var(var).ok()
+        }))
+        .map(std::sync::Arc::new);
+    if !args.project_roots.is_empty() {
+        let refs = cargo_gc::Referenced::load(&args.project_roots)?;
+        if refs.lockfiles == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no Cargo.lock found below --project-root",
+            ));
+        }
+        state.cargo_refs = Some(std::sync::Arc::new(refs));
+    }
+    run_tui(state, vec![tui::Effect::LoadSeeds { seeds }])
+}
+
+/// The TUI needs a real terminal on stdout; without one, or with
+/// `TERM=dumb`, `putzen caches` prints the ranked table instead.
+fn wants_plain_text(stdout_is_tty: bool, term: Option<&str>) -> bool {
+    !stdout_is_tty || term == Some("dumb")
+}
+
+/// Empty `State` with the "scanning folders" spinner up, ready for a
+/// loader effect to fill `all` via `Msg::SeedsLoaded`.
+pub(crate) fn initial_state(
+    title: &str,
+    floor: model::FloorPolicy,
+    dry_run: bool,
+    yes: bool,
+) -> tui::State {
+    use std::time::{Instant, SystemTime};
+
+    tui::State {
         now: SystemTime::now(),
+        title: title.to_string(),
         all: Vec::new(),
         sort: model::Sort::Score,
         marks: model::MarkSet::default(),
         cursor: 0,
         files_cursor: 0,
-        floor: model::FloorPolicy { floor },
+        floor,
         focus_right: false,
         stack: Vec::new(),
         stack_labels: Vec::new(),
         quit: false,
         modal: tui::Modal::None,
-        dry_run: args.dry_run,
-        yes_mode: args.yes,
+        dry_run,
+        yes_mode: yes,
         total_freed: 0,
         filter: None,
         loading: Some(tui::Loading {
@@ -63,10 +135,17 @@ pub fn run(args: CachesArgs) -> io::Result<()> {
         level_dirty: false,
         drill_paths: Vec::new(),
         cursor_stack: Vec::new(),
-    };
-
-    let initial_effects = vec![tui::Effect::LoadSeeds { seeds }];
+        seeds: Vec::new(),
+        cargo_refs: None,
+        cargo_last_use: None,
+    }
+}
 
+/// Own the terminal for one TUI session: enter the alternate screen, run the
+/// loop until the user quits, restore the terminal, then feed the freed
+/// total into the highscore board (real runs only).
+pub(crate) fn run_tui(state: tui::State, initial_effects: Vec<tui::Effect>) -> io::Result<()> {
+    let dry_run = state.dry_run;
     let mut term = tui::enter_tui()?;
     let loop_result = tui::run_loop(&mut term, state, initial_effects);
     let (final_state, total_freed) = match loop_result {
@@ -80,32 +159,158 @@ pub fn run(args: CachesArgs) -> io::Result<()> {
 
     tui::leave_tui(&mut term)?;
 
+    if !dry_run {
+        record_highscore(total_freed, false)?;
+    }
+    Ok(())
+}
+
+/// Feed the freed total of a real run into the highscore board. With
+/// `to_stderr` the medal and the first-run greeting stay off stdout, which
+/// then belongs to JSON.
+fn record_highscore(total_freed: u64, to_stderr: bool) -> io::Result<()> {
     #[cfg(feature = "highscore-board")]
-    if !args.dry_run && total_freed > 0 {
+    if total_freed > 0 {
         use crate::RunObserver;
-        let mut obs = crate::HighscoreObserver::load()?;
-        if let Some(medal) = obs.on_run_complete(total_freed) {
-            println!("{medal}");
+        let mut obs = if to_stderr {
+            crate::HighscoreObserver::load_quietly()?
+        } else {
+            crate::HighscoreObserver::load()?
+        };
+        match obs.on_run_complete(total_freed) {
+            Some(medal) if to_stderr => eprintln!("{medal}"),
+            Some(medal) => println!("{medal}"),
+            None => {}
         }
     }
 
     #[cfg(not(feature = "highscore-board"))]
-    let _ = total_freed;
+    let _ = (total_freed, to_stderr);
 
     Ok(())
 }
 
-/// Accepts a duration like "24h", "7d", "2w", or "1y". Returns Err on parse failure.
+/// `--list` and `--delete-older-than`: scan on this thread, then print the
+/// ranked table or delete the matching caches. Never touches the terminal
+/// beyond stdout and, without `-y`, one confirmation prompt.
+fn run_batch(
+    args: &CachesArgs,
+    home: &std::path::Path,
+    seeds: &[PathBuf],
+    floor: model::FloorPolicy,
+) -> io::Result<()> {
+    use crate::cleaner::{DoCleanUp, DryRunCleaner, ProperCleaner};
+    use std::io::IsTerminal;
+    use std::time::SystemTime;
+
+    let now = SystemTime::now();
+    let mut caches = listing::ranked(scan::collect(seeds), floor, now);
+    caches.retain(|c| c.size_bytes >= args.min_size);
+
+    let Some(older_than) = args.delete_older_than else {
+        if args.json {
+            let rows: Vec<_> = caches
+                .iter()
+                .map(|c| listing::CacheRow::new(c, floor, now))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&rows)?);
+        } else {
+            print!("{}", listing::render_table(&caches, floor, now, Some(home)));
+        }
+        return Ok(());
+    };
+
+    let prune = listing::Prune {
+        older_than,
+        min_size: args.min_size,
+    };
+    caches.retain(|c| prune.matches(c, floor, now));
+
+    if !args.json {
+        print!("{}", listing::render_table(&caches, floor, now, Some(home)));
+    }
+    if !caches.is_empty() && !args.dry_run && !args.yes {
+        if !io::stdin().is_terminal() {
+            return Err(io::Error::other(
+                "refusing to delete caches without confirmation, pass -y to confirm",
+            ));
+        }
+        let total: u64 = caches.iter().map(|c| c.size_bytes).sum();
+        let confirmed = dialoguer::Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
+            .with_prompt(format!(
+                "delete {} cache(s), {}?",
+                caches.len(),
+                format::human_size(total)
+            ))
+            .default(false)
+            .interact()
+            .map_err(io::Error::other)?;
+        if !confirmed {
+            return Ok(());
+        }
+    }
+
+    let cleaner: Box<dyn DoCleanUp> = if args.dry_run {
+        Box::new(DryRunCleaner)
+    } else {
+        Box::new(ProperCleaner)
+    };
+    let outcome = listing::delete(&caches, cleaner.as_ref(), args.dry_run, floor, now);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&outcome)?);
+    } else {
+        for failure in &outcome.failed {
+            eprintln!(
+                "failed to delete {}: {}",
+                failure.path.display(),
+                failure.error
+            );
+        }
+        if outcome.fixed_permissions > 0 {
+            println!(
+                "made {} read-only folder(s) inside writable to delete them",
+                outcome.fixed_permissions
+            );
+        }
+        let (deleted, freed) = if args.dry_run {
+            ("would delete", "freeing")
+        } else {
+            ("deleted", "freed")
+        };
+        println!(
+            "{deleted} {} cache(s), {freed} {}",
+            outcome.deleted.len(),
+            format::human_size(outcome.freed_bytes)
+        );
+    }
+
+    if !args.dry_run {
+        record_highscore(outcome.freed_bytes, args.json)?;
+    }
+    if outcome.failed.is_empty() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "{} cache(s) could not be deleted",
+            outcome.failed.len()
+        )))
+    }
+}
+
+/// Accepts a duration like "30s", "5m", "24h", "7d", "2w", or "1y". Returns Err on parse failure.
 pub fn parse_duration(s: &str) -> Result<std::time::Duration, String> {
     use std::time::Duration;
     let (num, unit) = s.split_at(s.len().saturating_sub(1));
     let n: u64 = num.parse().map_err(|_| format!("bad duration `{s}`"))?;
     match unit {
+        "s" => Ok(Duration::from_secs(n)),
+        "m" => Ok(Duration::from_secs(n * 60)),
         "h" => Ok(Duration::from_secs(n * 3_600)),
         "d" => Ok(Duration::from_secs(n * 86_400)),
         "w" => Ok(Duration::from_secs(n * 7 * 86_400)),
         "y" => Ok(Duration::from_secs(n * 365 * 86_400)),
-        _ => Err(format!("bad duration unit in `{s}`, expected h|d|w|y")),
+        _ => Err(format!("bad duration unit in `{s}`, expected s|m|h|d|w|y")),
     }
 }
 
@@ -114,6 +319,8 @@ mod parse_duration_tests {
     use super::*;
     #[test]
     fn parses_hours_days_years() {
+        assert_eq!(parse_duration("30s").unwrap().as_secs(), 30);
+        assert_eq!(parse_duration("5m").unwrap().as_secs(), 300);
         assert_eq!(parse_duration("24h").unwrap().as_secs(), 24 * 3600);
         assert_eq!(parse_duration("7d").unwrap().as_secs(), 7 * 86_400);
         assert_eq!(parse_duration("1y").unwrap().as_secs(), 365 * 86_400);
@@ -125,28 +332,150 @@ mod parse_duration_tests {
     }
 }
 
+/// Accepts a size like "500M", "20G" or "1.5T" in binary (1024-based)
+/// units, matching `HumanReadable`. A trailing `B` / `iB` is optional, and a
+/// bare number is bytes. Returns Err on parse failure.
+pub fn parse_size(s: &str) -> Result<u64, String> {
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let n: f64 = num.parse().map_err(|_| format!("bad size `{s}`"))?;
+    let unit = unit
+        .strip_suffix("iB")
+        .or(unit.strip_suffix('B'))
+        .unwrap_or(unit);
+    let factor: u64 = match unit.to_ascii_uppercase().as_str() {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        _ => return Err(format!("bad size unit in `{s}`, expected K|M|G|T")),
+    };
+    Ok((n * factor as f64) as u64)
+}
+
+#[cfg(test)]
+mod parse_size_tests {
+    use super::*;
+    #[test]
+    fn parses_binary_units() {
+        assert_eq!(parse_size("512").unwrap(), 512);
+        assert_eq!(parse_size("500M").unwrap(), 500 << 20);
+        assert_eq!(parse_size("20G").unwrap(), 20 << 30);
+        assert_eq!(parse_size("20GiB").unwrap(), 20 << 30);
+        assert_eq!(parse_size("1.5k").unwrap(), 1536);
+    }
+    #[test]
+    fn rejects_garbage() {
+        assert!(parse_size("").is_err());
+        assert!(parse_size("G").is_err());
+        assert!(parse_size("7x").is_err());
+    }
+}
+
 /// Resolve a HOME-relative path string against `$HOME`. Absolute paths
-/// (`/...`) pass through unchanged.
+/// (`/...`) pass through unchanged, a leading `~/` is HOME-relative too.
 pub fn resolve_path(home: &std::path::Path, raw: &str) -> std::path::PathBuf {
     if raw.starts_with('/') {
         std::path::PathBuf::from(raw)
     } else {
-        home.join(raw)
+        home.join(raw.strip_prefix("~/").unwrap_or(raw))
+    }
+}
+
+/// Where `seed` lives: under `$env` when the tool's variable is set (the
+/// tool itself looks there first), else under `$XDG_CACHE_HOME` for paths
+/// in `.cache/`, else at its HOME-relative default.
+pub fn resolve_seed(
+    home: &std::path::Path,
+    seed: &dropins::Seed,
+    env: &impl Fn(&str) -> Option<String>,
+) -> PathBuf {
+    let var = |name: &str| env(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+    if let Some(dir) = seed.env.as_deref().and_then(var) {
+        return match &seed.suffix {
+            Some(suffix) => dir.join(suffix),
+            None => dir,
+        };
+    }
+    if let Some(xdg) = var("XDG_CACHE_HOME") {
+        if seed.path == ".cache" {
+            return xdg;
+        }
+        if let Some(rest) = seed.path.strip_prefix(".cache/") {
+            return xdg.join(rest);
+        }
     }
+    resolve_path(home, &seed.path)
+}
+
+/// Risk and note of every catalogue entry that exists on disk, keyed by
+/// its canonical path like the scanned rows.
+pub fn seed_infos(
+    home: &std::path::Path,
+    catalogue: &[dropins::Seed],
+    env: impl Fn(&str) -> Option<String>,
+) -> Vec<model::SeedInfo> {
+    catalogue
+        .iter()
+        .filter_map(|seed| {
+            let path = resolve_seed(home, seed, &env).canonicalize().ok()?;
+            Some(model::SeedInfo {
+                path,
+                label: seed.label.clone(),
+                risk: seed.risk,
+                tool: seed.tool.clone(),
+                note: seed.notes.clone(),
+            })
+        })
+        .collect()
+}
+
+/// How the command line shapes the seed set.
+#[derive(Clone, Debug, Default)]
+pub struct SeedSelection {
+    /// `--root`: scan these instead of the catalogue.
+    pub roots: Vec<PathBuf>,
+    /// `--add-root`: scan these on top.
+    pub add_roots: Vec<PathBuf>,
+    /// `--disable`: catalogue labels to leave out.
+    pub disable: Vec<String>,
 }
 
 /// Pick the seed set for the scan: `--root` values when given, otherwise
-/// the built-in defaults resolved against `home`. The two are alternatives
-/// rather than complementary — passing `--root` is the user telling us
+/// the catalogue (built-in defaults plus drop-ins) resolved through
+/// [`resolve_seed`], minus the `--disable`d labels. `--add-root` extends either. `--root`
+/// replaces rather than complements — passing it is the user telling us
 /// "scan this tree, not the usual ones".
-pub fn select_seeds(home: &std::path::Path, roots: &[PathBuf]) -> Vec<PathBuf> {
-    if roots.is_empty() {
-        defaults::defaults()
-            .map(|r| resolve_path(home, r.path))
+pub fn select_seeds(
+    home: &std::path::Path,
+    catalogue: &[dropins::Seed],
+    selection: &SeedSelection,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<PathBuf>, String> {
+    if let Some(unknown) = selection
+        .disable
+        .iter()
+        .find(|label| !catalogue.iter().any(|s| &s.label == *label))
+    {
+        return Err(format!("--disable: no cache labelled `{unknown}`"));
+    }
+    if !selection.roots.is_empty() && !selection.disable.is_empty() {
+        return Err("--disable has no effect with --root, which replaces the defaults".into());
+    }
+    let mut seeds: Vec<PathBuf> = if selection.roots.is_empty() {
+        catalogue
+            .iter()
+            .filter(|s| !selection.disable.contains(&s.label))
+            .map(|s| resolve_seed(home, s, &env))
             .collect()
     } else {
-        roots.to_vec()
-    }
+        selection.roots.clone()
+    };
+    seeds.extend(selection.add_roots.iter().cloned());
+    Ok(seeds)
 }
 
 #[cfg(test)]
@@ -172,10 +501,104 @@ mod tests {
         );
     }
 
+    #[test]
+    fn plain_text_without_a_capable_terminal() {
+        assert!(!wants_plain_text(true, Some("xterm-256color")));
+        assert!(!wants_plain_text(true, None));
+        assert!(wants_plain_text(true, Some("dumb")));
+        assert!(wants_plain_text(false, Some("xterm-256color")));
+    }
+
+    #[test]
+    fn resolve_tilde_prefix() {
+        let home = PathBuf::from("/u/sven");
+        assert_eq!(
+            resolve_path(&home, "~/.cache/bazel"),
+            PathBuf::from("/u/sven/.cache/bazel")
+        );
+    }
+
+    fn catalogue() -> Vec<dropins::Seed> {
+        dropins::catalogue(Vec::new())
+    }
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    fn seed(path: &str, env: Option<&str>, suffix: Option<&str>) -> dropins::Seed {
+        dropins::Seed {
+            label: "x".into(),
+            path: path.into(),
+            tool: None,
+            notes: None,
+            risk: None,
+            env: env.map(String::from),
+            suffix: suffix.map(String::from),
+        }
+    }
+
+    #[test]
+    fn resolve_seed_prefers_the_tool_variable() {
+        let home = PathBuf::from("/u/sven");
+        let env = |var: &str| (var == "CARGO_HOME").then(|| "/opt/cargo".to_string());
+        let cargo = seed(
+            ".cargo/registry/cache",
+            Some("CARGO_HOME"),
+            Some("registry/cache"),
+        );
+        assert_eq!(
+            resolve_seed(&home, &cargo, &env),
+            PathBuf::from("/opt/cargo/registry/cache")
+        );
+        let ollama = seed(".ollama/models", Some("OLLAMA_MODELS"), None);
+        let env = |var: &str| (var == "OLLAMA_MODELS").then(|| "/data/models".to_string());
+        assert_eq!(
+            resolve_seed(&home, &ollama, &env),
+            PathBuf::from("/data/models")
+        );
+        assert_eq!(
+            resolve_seed(&home, &cargo, &no_env),
+            PathBuf::from("/u/sven/.cargo/registry/cache")
+        );
+    }
+
+    #[test]
+    fn resolve_seed_ignores_empty_variables() {
+        let home = PathBuf::from("/u/sven");
+        let env = |_: &str| Some(String::new());
+        let npm = seed(".npm", Some("npm_config_cache"), None);
+        assert_eq!(
+            resolve_seed(&home, &npm, &env),
+            PathBuf::from("/u/sven/.npm")
+        );
+    }
+
+    #[test]
+    fn resolve_seed_moves_dot_cache_with_xdg_cache_home() {
+        let home = PathBuf::from("/u/sven");
+        let env = |var: &str| (var == "XDG_CACHE_HOME").then(|| "/scratch/cache".to_string());
+        let pip = seed(".cache/pip", Some("PIP_CACHE_DIR"), None);
+        assert_eq!(
+            resolve_seed(&home, &pip, &env),
+            PathBuf::from("/scratch/cache/pip")
+        );
+        let cache = seed(".cache", None, None);
+        assert_eq!(
+            resolve_seed(&home, &cache, &env),
+            PathBuf::from("/scratch/cache")
+        );
+        let npm = seed(".npm", None, None);
+        assert_eq!(
+            resolve_seed(&home, &npm, &env),
+            PathBuf::from("/u/sven/.npm")
+        );
+    }
+
     #[test]
     fn select_seeds_no_roots_uses_defaults() {
         let home = PathBuf::from("/u/sven");
-        let seeds = select_seeds(&home, &[]);
+        let seeds = select_seeds(&home, &catalogue(), &SeedSelection::default(), no_env).unwrap();
         assert!(!seeds.is_empty(), "default seeds must be populated");
         assert!(
             seeds.iter().any(|p| p.starts_with(&home)),
@@ -187,7 +610,49 @@ mod tests {
     fn select_seeds_with_roots_replaces_defaults() {
         let home = PathBuf::from("/u/sven");
         let roots = vec![PathBuf::from("/tmp/scratch"), PathBuf::from("/var/cache")];
-        let seeds = select_seeds(&home, &roots);
+        let selection = SeedSelection {
+            roots: roots.clone(),
+            ..Default::default()
+        };
+        let seeds = select_seeds(&home, &catalogue(), &selection, no_env).unwrap();
         assert_eq!(seeds, roots, "--root replaces, never extends");
     }
+
+    #[test]
+    fn select_seeds_add_root_extends_and_disable_drops() {
+        let home = PathBuf::from("/u/sven");
+        let all = select_seeds(&home, &catalogue(), &SeedSelection::default(), no_env).unwrap();
+        let selection = SeedSelection {
+            add_roots: vec![PathBuf::from("/srv/cache")],
+            disable: vec!["npm".into()],
+            ..Default::default()
+        };
+        let seeds = select_seeds(&home, &catalogue(), &selection, no_env).unwrap();
+        assert_eq!(seeds.len(), all.len());
+        assert_eq!(seeds.last(), Some(&PathBuf::from("/srv/cache")));
+        assert!(!seeds.iter().any(|p| p.ends_with(".npm")));
+    }
+
+    #[test]
+    fn select_seeds_rejects_unknown_disable_label() {
+        let selection = SeedSelection {
+            disable: vec!["no such cache".into()],
+            ..Default::default()
+        };
+        let err =
+            select_seeds(&PathBuf::from("/u/sven"), &catalogue(), &selection, no_env).unwrap_err();
+        assert!(err.contains("no such cache"), "{err}");
+    }
+
+    #[test]
+    fn select_seeds_rejects_disable_with_roots() {
+        let selection = SeedSelection {
+            roots: vec![PathBuf::from("/tmp/scratch")],
+            disable: vec![catalogue()[0].label.clone()],
+            ..Default::default()
+        };
+        let err =
+            select_seeds(&PathBuf::from("/u/sven"), &catalogue(), &selection, no_env).unwrap_err();
+        assert!(err.contains("--root"), "{err}");
+    }
 }
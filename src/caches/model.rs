@@ -1,8 +1,12 @@
 //! Pure data types for the cache TUI.
 
+use crate::caches::defaults::Risk;
 use std::path::PathBuf;
 use std::time::{Duration, SystemTime};
 
+/// Label of the virtual row of blobs nothing in a model store uses.
+pub const ORPHANED: &str = "orphaned blobs";
+
 #[derive(Clone, Debug)]
 pub struct Cache {
     /// Display name (label derived at scan time).
@@ -18,6 +22,11 @@ pub struct Cache {
     pub top_files: Vec<TopFile>,
     /// Count of dir entries that could not be read (permission, dangling symlink).
     pub unreadable: u64,
+    /// Non-empty for a virtual row (e.g. cargo's "unreferenced") that stands
+    /// for these entries instead of a directory of its own, and for a folder
+    /// around a nested seed, which stands for everything beside the seed.
+    /// Deleting the row deletes them.
+    pub members: Vec<Cache>,
 }
 
 #[derive(Clone, Debug)]
@@ -28,6 +37,32 @@ pub struct TopFile {
 }
 
 impl Cache {
+    /// A virtual row summing up `members`; its top files are the members
+    /// themselves, so the details pane lists what would go.
+    pub fn virtual_row(label: String, path: PathBuf, members: Vec<Cache>) -> Cache {
+        let mut top_files: Vec<TopFile> = members
+            .iter()
+            .map(|m| TopFile {
+                name: m.label.clone(),
+                size_bytes: m.size_bytes,
+                mtime: m.newest_mtime,
+            })
+            .collect();
+        top_files.sort_by_key(|f| std::cmp::Reverse(f.size_bytes));
+        top_files.truncate(64);
+        Cache {
+            label,
+            path,
+            size_bytes: members.iter().map(|m| m.size_bytes).sum(),
+            newest_mtime: members.iter().filter_map(|m| m.newest_mtime).max(),
+            file_count: members.iter().map(|m| m.file_count).sum(),
+            dir_count: members.iter().map(|m| m.dir_count).sum(),
+            top_files,
+            unreadable: members.iter().map(|m| m.unreadable).sum(),
+            members,
+        }
+    }
+
     /// Duration since the newest file was touched. `None` for empty caches.
     pub fn age(&self, now: SystemTime) -> Option<Duration> {
         let mtime = self.newest_mtime?;
@@ -88,6 +123,18 @@ impl MarkSet {
     }
 }
 
+/// Catalogue facts about a seed, for the rows found below it.
+#[derive(Clone, Debug)]
+pub struct SeedInfo {
+    /// Canonical path the seed resolved to.
+    pub path: PathBuf,
+    pub label: String,
+    pub risk: Option<Risk>,
+    /// The tool that fills the cache, from a drop-in.
+    pub tool: Option<String>,
+    pub note: Option<String>,
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct FloorPolicy {
     pub floor: Duration,
@@ -118,6 +165,7 @@ mod tests {
             dir_count: 0,
             top_files: Vec::new(),
             unreadable: 0,
+            members: Vec::new(),
         }
     }
 
@@ -0,0 +1,357 @@
+//! Ollama model store analyzer.
+//!
+//! `~/.ollama/models` (or `$OLLAMA_MODELS`) holds
+//!
+//! - `manifests/<registry>/<namespace>/<name>/<tag>`: JSON listing the
+//!   layers of one model tag by digest,
+//! - `blobs/sha256-<hex>`: the layers, shared between tags.
+//!
+//! Drilling into the store shows one row per `name:tag`, sized by what
+//! deleting it frees: its manifest and only the blobs no other manifest
+//! references. The layers it shares are still listed among its files.
+
+use super::model::{Cache, TopFile, ORPHANED};
+use super::scan::{read_names, stat_file};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Models pulled without a namespace live here, shown as plain `name:tag`.
+const DEFAULT_REGISTRY: &str = "registry.ollama.ai";
+const DEFAULT_NAMESPACE: &str = "library";
+
+#[derive(Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    config: Option<Layer>,
+    #[serde(default)]
+    layers: Vec<Layer>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Layer {
+    media_type: String,
+    digest: String,
+}
+
+impl Layer {
+    /// `sha256:<hex>` is stored as `blobs/sha256-<hex>`.
+    fn blob_name(&self) -> String {
+        self.digest.replace(':', "-")
+    }
+
+    /// `application/vnd.ollama.image.model` → `model`.
+    fn kind(&self) -> &str {
+        self.media_type
+            .rsplit('.')
+            .next()
+            .unwrap_or(&self.media_type)
+    }
+}
+
+/// `name:tag` for a manifest path relative to `manifests`, with the registry
+/// and namespace spelled out unless they are Ollama's defaults.
+pub fn model_label(rel: &Path) -> String {
+    let parts: Vec<String> = rel
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    match parts.as_slice() {
+        [registry, namespace, name, tag]
+            if registry == DEFAULT_REGISTRY && namespace == DEFAULT_NAMESPACE =>
+        {
+            format!("{name}:{tag}")
+        }
+        [registry, namespace, name, tag] if registry == DEFAULT_REGISTRY => {
+            format!("{namespace}/{name}:{tag}")
+        }
+        [repo @ .., tag] if !repo.is_empty() => format!("{}:{tag}", repo.join("/")),
+        _ => rel.display().to_string(),
+    }
+}
+
+/// One row per model tag in the store `dir`, plus an orphaned blobs row.
+/// `None` when `dir` is not an Ollama store, or one of its manifests can't
+/// be read: guessing would offer blobs still in use for deletion.
+pub fn model_rows(dir: &Path) -> Option<Vec<Cache>> {
+    let manifests_dir = dir.join("manifests");
+    let blobs_dir = dir.join("blobs");
+    if !manifests_dir.is_dir() || !blobs_dir.is_dir() {
+        return None;
+    }
+    let mut manifests = Vec::new();
+    for rel in manifest_files(&manifests_dir) {
+        let text = std::fs::read_to_string(manifests_dir.join(&rel)).ok()?;
+        let manifest: Manifest = serde_json::from_str(&text).ok()?;
+        // The config's media type is a docker one; call it what it is.
+        let config = manifest.config.map(|config| Layer {
+            media_type: "config".into(),
+            ..config
+        });
+        let layers: Vec<Layer> = config.into_iter().chain(manifest.layers).collect();
+        manifests.push((rel, layers));
+    }
+
+    // How many manifests reference each blob.
+    let mut users: HashMap<String, usize> = HashMap::new();
+    for (_, layers) in &manifests {
+        let mut blobs: Vec<String> = layers.iter().map(Layer::blob_name).collect();
+        blobs.sort_unstable();
+        blobs.dedup();
+        for blob in blobs {
+            *users.entry(blob).or_default() += 1;
+        }
+    }
+
+    let mut rows = Vec::new();
+    for (rel, layers) in &manifests {
+        let manifest = manifests_dir.join(rel);
+        let mut members: Vec<Cache> = stat_file(&manifest, "manifest".into())
+            .into_iter()
+            .collect();
+        let mut top_files = Vec::new();
+        let mut seen = Vec::new();
+        for layer in layers {
+            let blob = layer.blob_name();
+            if seen.contains(&blob) {
+                continue;
+            }
+            let Some(cache) = stat_file(&blobs_dir.join(&blob), layer.kind().to_string()) else {
+                continue;
+            };
+            let shared = users.get(&blob).is_some_and(|&n| n > 1);
+            top_files.push(TopFile {
+                name: if shared {
+                    format!("{} (shared)", layer.kind())
+                } else {
+                    layer.kind().to_string()
+                },
+                size_bytes: cache.size_bytes,
+                mtime: cache.newest_mtime,
+            });
+            if !shared {
+                members.push(cache);
+            }
+            seen.push(blob);
+        }
+        let mut row = Cache::virtual_row(model_label(rel), manifest, members);
+        top_files.sort_by_key(|f| std::cmp::Reverse(f.size_bytes));
+        row.top_files = top_files;
+        rows.push(row);
+    }
+
+    // `sha256-<hex>-partial*` are the chunks of a pull in progress.
+    let orphans: Vec<Cache> = read_names(&blobs_dir)
+        .into_iter()
+        .filter(|blob| !users.contains_key(blob) && !blob.contains("-partial"))
+        .filter_map(|blob| stat_file(&blobs_dir.join(&blob), blob))
+        .collect();
+    if !orphans.is_empty() {
+        rows.push(Cache::virtual_row(
+            ORPHANED.to_string(),
+            dir.join(ORPHANED),
+            orphans,
+        ));
+    }
+    Some(rows)
+}
+
+/// Every file below `manifests`, relative to it.
+fn manifest_files(manifests: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(rel) = pending.pop() {
+        for name in read_names(&manifests.join(&rel)) {
+            let rel = rel.join(name);
+            match std::fs::symlink_metadata(manifests.join(&rel)) {
+                Ok(meta) if meta.is_dir() => pending.push(rel),
+                Ok(meta) if meta.is_file() => files.push(rel),
+                _ => {}
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(config: &str, layers: &[(&str, &str)]) -> String {
+        let layers: Vec<String> = layers
+            .iter()
+            .map(|(kind, digest)| {
+                format!(
+                    r#"{{"mediaType":"application/vnd.ollama.image.{kind}","digest":"sha256:{digest}","size":1}}"#
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"schemaVersion":2,"config":{{"mediaType":"application/vnd.docker.container.image.v1+json","digest":"sha256:{config}","size":1}},"layers":[{}]}}"#,
+            layers.join(",")
+        )
+    }
+
+    fn store() -> tempfile::TempDir {
+        let store = tempfile::tempdir().unwrap();
+        fill(store.path());
+        store
+    }
+
+    /// `llama3:8b` and `llama3:latest` share weights and template but have
+    /// their own config; `acme/coder:1b` stands alone; one blob is unused
+    /// and one is still being pulled.
+    fn fill(store: &Path) {
+        let blobs = store.join("blobs");
+        std::fs::create_dir_all(&blobs).unwrap();
+        for (digest, size) in [
+            ("weights", 4_000),
+            ("tmpl", 10),
+            ("cfg1", 5),
+            ("cfg2", 6),
+            ("coder", 1_000),
+            ("cfg3", 7),
+            ("lost", 300),
+            ("pulling-partial-0", 50),
+        ] {
+            std::fs::write(blobs.join(format!("sha256-{digest}")), vec![0u8; size]).unwrap();
+        }
+        let registry = store.join("manifests/registry.ollama.ai");
+        let library = registry.join("library/llama3");
+        std::fs::create_dir_all(&library).unwrap();
+        let shared = [("model", "weights"), ("template", "tmpl")];
+        std::fs::write(library.join("8b"), manifest("cfg1", &shared)).unwrap();
+        std::fs::write(library.join("latest"), manifest("cfg2", &shared)).unwrap();
+        let coder = registry.join("acme/coder");
+        std::fs::create_dir_all(&coder).unwrap();
+        std::fs::write(coder.join("1b"), manifest("cfg3", &[("model", "coder")])).unwrap();
+    }
+
+    #[test]
+    fn labels_models_like_ollama_list() {
+        let label = |p: &str| model_label(Path::new(p));
+        assert_eq!(label("registry.ollama.ai/library/llama3/8b"), "llama3:8b");
+        assert_eq!(label("registry.ollama.ai/acme/coder/1b"), "acme/coder:1b");
+        assert_eq!(
+            label("hf.co/acme/tiny-GGUF/Q4_K_M"),
+            "hf.co/acme/tiny-GGUF:Q4_K_M"
+        );
+    }
+
+    #[test]
+    fn rows_are_sized_by_their_exclusive_blobs_and_list_shared_ones() {
+        let store = store();
+        let rows = model_rows(store.path()).unwrap();
+        let labels: Vec<_> = rows.iter().map(|r| r.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            ["acme/coder:1b", "llama3:8b", "llama3:latest", ORPHANED]
+        );
+
+        let llama = &rows[1];
+        let manifest_len = llama.members[0].size_bytes;
+        assert_eq!(llama.size_bytes, 5 + manifest_len);
+        let members: Vec<_> = llama.members.iter().map(|m| m.label.as_str()).collect();
+        assert_eq!(members, ["manifest", "config"], "weights are shared");
+        assert_eq!(
+            llama.members[0].path,
+            store
+                .path()
+                .join("manifests/registry.ollama.ai/library/llama3/8b")
+        );
+        assert_eq!(
+            llama.members[1].path,
+            store.path().join("blobs/sha256-cfg1")
+        );
+        assert_eq!(llama.top_files[0].name, "model (shared)");
+        assert_eq!(llama.top_files[0].size_bytes, 4_000);
+
+        let coder = &rows[0];
+        assert!(coder
+            .members
+            .iter()
+            .any(|m| m.path == store.path().join("blobs/sha256-coder")));
+
+        assert_eq!(rows[3].size_bytes, 300, "pulls in progress stay");
+    }
+
+    #[test]
+    fn the_default_seed_shows_models_not_folders() {
+        let home = tempfile::tempdir().unwrap();
+        let home = home.path().canonicalize().unwrap();
+        fill(&home.join(".ollama/models"));
+        let catalogue = crate::caches::dropins::catalogue(Vec::new());
+        let seeds =
+            crate::caches::select_seeds(&home, &catalogue, &Default::default(), |_| None).unwrap();
+
+        let rows = crate::caches::scan::collect(&seeds);
+        let labels: Vec<_> = rows.iter().map(|r| r.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            ["acme/coder:1b", "llama3:8b", "llama3:latest", ORPHANED]
+        );
+    }
+
+    #[test]
+    fn pruning_tags_that_share_weights_counts_only_what_is_freed() {
+        use crate::caches::listing::{delete, Prune};
+        use crate::caches::model::FloorPolicy;
+        use std::time::{Duration, SystemTime};
+
+        let store = store();
+        let rows = model_rows(store.path()).unwrap();
+        let floor = FloorPolicy {
+            floor: Duration::ZERO,
+        };
+        let now = SystemTime::now() + Duration::from_secs(60);
+        let prune = Prune {
+            older_than: Duration::ZERO,
+            min_size: 1_000,
+        };
+        let picked: Vec<Cache> = rows
+            .into_iter()
+            .filter(|c| prune.matches(c, floor, now))
+            .collect();
+        let labels: Vec<_> = picked.iter().map(|r| r.label.as_str()).collect();
+        assert_eq!(labels, ["acme/coder:1b"], "shared weights are not theirs");
+
+        let llama = model_rows(store.path())
+            .unwrap()
+            .into_iter()
+            .filter(|r| r.label.starts_with("llama3:"))
+            .collect::<Vec<_>>();
+        let exclusive: u64 = llama.iter().map(|r| r.size_bytes).sum();
+        let outcome = delete(&llama, &crate::cleaner::ProperCleaner, false, floor, now);
+        assert_eq!(outcome.deleted.len(), 2);
+        assert_eq!(outcome.freed_bytes, exclusive);
+        assert!(exclusive < 4_000);
+        let blobs = store.path().join("blobs");
+        assert!(!blobs.join("sha256-cfg1").exists());
+        assert!(!blobs.join("sha256-cfg2").exists());
+        assert!(
+            blobs.join("sha256-weights").exists(),
+            "each tag saw the weights as shared"
+        );
+    }
+
+    #[test]
+    fn unreadable_manifest_keeps_the_plain_listing() {
+        let store = store();
+        std::fs::write(
+            store
+                .path()
+                .join("manifests/registry.ollama.ai/library/llama3/broken"),
+            "{",
+        )
+        .unwrap();
+        assert!(model_rows(store.path()).is_none());
+    }
+
+    #[test]
+    fn not_a_model_store() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(model_rows(dir.path()).is_none());
+    }
+}
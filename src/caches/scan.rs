@@ -19,6 +19,12 @@ pub fn stat_dir(root: &Path) -> Cache {
 /// during the walk. The callback is the per-cache progress hook used by the
 /// startup scan to feed `Msg::ScanProgress` to the TUI.
 pub fn stat_dir_with_progress(root: &Path, on_dir: &mut dyn FnMut()) -> Cache {
+    stat_dir_excluding(root, &[], on_dir)
+}
+
+/// Same as `stat_dir_with_progress` but never enters the directories in
+/// `exclude`, so a seed nested inside `root` is not counted twice.
+pub fn stat_dir_excluding(root: &Path, exclude: &[PathBuf], on_dir: &mut dyn FnMut()) -> Cache {
     let mut size_bytes = 0u64;
     let mut newest = None::<SystemTime>;
     let mut file_count = 0u64;
@@ -30,9 +36,15 @@ pub fn stat_dir_with_progress(root: &Path, on_dir: &mut dyn FnMut()) -> Cache {
     // dotfiles (`.lock`, `.tmp`, `.index`); excluding them would shift
     // newest_mtime onto the OLDER visible files and make active caches
     // look dormant.
+    let exclude = exclude.to_vec();
     for entry in WalkDir::new(root)
         .follow_links(false)
         .skip_hidden(false)
+        .process_read_dir(move |_, _, _, children| {
+            if !exclude.is_empty() {
+                children.retain(|c| c.as_ref().map_or(true, |e| !exclude.contains(&e.path())));
+            }
+        })
         .into_iter()
         .flatten()
     {
@@ -95,6 +107,7 @@ pub fn stat_dir_with_progress(root: &Path, on_dir: &mut dyn FnMut()) -> Cache {
         dir_count,
         top_files,
         unreadable,
+        members: Vec::new(),
     }
 }
 
@@ -118,8 +131,50 @@ pub fn enumerate_seed_with_progress(seed: &Path, on_dir: &mut dyn FnMut()) -> Ve
         .collect()
 }
 
+/// The names in `dir`, sorted; empty when it can't be read.
+pub fn read_names(dir: &Path) -> Vec<String> {
+    let Ok(read) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = read
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+/// A folder or a single file as a row labelled with its name.
+pub fn stat_entry(path: &Path, is_dir: bool) -> Option<Cache> {
+    if is_dir {
+        return Some(stat_dir(path));
+    }
+    stat_file(path, path.file_name()?.to_string_lossy().to_string())
+}
+
+/// A single file as a row of its own, labelled `label`.
+pub fn stat_file(path: &Path, label: String) -> Option<Cache> {
+    let meta = std::fs::symlink_metadata(path).ok()?;
+    Some(Cache {
+        label,
+        path: path.to_path_buf(),
+        size_bytes: meta.len(),
+        newest_mtime: meta.modified().ok(),
+        file_count: 1,
+        dir_count: 0,
+        top_files: Vec::new(),
+        unreadable: 0,
+        members: Vec::new(),
+    })
+}
+
 /// Walk every seed and concatenate, de-duplicating by canonicalised absolute
-/// path. Order is preserved (first occurrence wins).
+/// path. Order is preserved (first occurrence wins). A seed nested inside
+/// another (`.cache/sccache` in `.cache`) only shows up under its own seed:
+/// the outer seed skips it and leaves it out of its sizes. An Ollama store
+/// as a seed yields its `name:tag` rows instead of its folders. A row holding a
+/// seed deeper down (`.cache/tools` around `.cache/tools/bazel/disk`) gets
+/// everything else in it as members, so deleting the row leaves the seed be.
 pub fn collect(seeds: &[PathBuf]) -> Vec<Cache> {
     collect_with_progress(seeds, &mut || {})
 }
@@ -130,18 +185,43 @@ pub fn collect(seeds: &[PathBuf]) -> Vec<Cache> {
 pub fn collect_with_progress(seeds: &[PathBuf], on_dir: &mut dyn FnMut()) -> Vec<Cache> {
     let mut seen = HashSet::new();
     let mut out = Vec::new();
-    for s in seeds {
-        let Ok(canonical) = s.canonicalize() else {
+    let canonical: Vec<PathBuf> = seeds.iter().filter_map(|s| s.canonicalize().ok()).collect();
+    for seed in &canonical {
+        // A seed that is a model store shows its models, not `blobs/` and
+        // `manifests/`, whose rows would delete blobs other models use.
+        if let Some(rows) = super::ollama::model_rows(seed) {
+            for c in rows {
+                if seen.insert(c.path.clone()) {
+                    out.push(c);
+                }
+            }
             continue;
-        };
-        let Ok(read) = std::fs::read_dir(&canonical) else {
+        }
+        let Ok(read) = std::fs::read_dir(seed) else {
             continue;
         };
+        let nested = nested_in(seed, &canonical);
         for entry in read.flatten() {
             if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
                 continue;
             }
-            let c = stat_dir_with_progress(&entry.path(), on_dir);
+            let path = entry.path();
+            if nested.contains(&path) {
+                continue;
+            }
+            let inside: Vec<PathBuf> = nested
+                .iter()
+                .filter(|n| n.starts_with(&path))
+                .cloned()
+                .collect();
+            let mut c = stat_dir_excluding(&path, &inside, on_dir);
+            if !inside.is_empty() {
+                c.members = members_around(&path, &path, &inside, on_dir);
+                if c.members.is_empty() {
+                    // nothing in here but the nested seeds
+                    continue;
+                }
+            }
             let canon = c.path.canonicalize().unwrap_or_else(|_| c.path.clone());
             if seen.insert(canon) {
                 out.push(c);
@@ -151,6 +231,72 @@ pub fn collect_with_progress(seeds: &[PathBuf], on_dir: &mut dyn FnMut()) -> Vec
     out
 }
 
+/// Everything below `dir` that holds none of `nested`, as rows labelled
+/// relative to `row`: the entries a row around nested seeds deletes.
+fn members_around(
+    row: &Path,
+    dir: &Path,
+    nested: &[PathBuf],
+    on_dir: &mut dyn FnMut(),
+) -> Vec<Cache> {
+    let Ok(read) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<_> = read.flatten().collect();
+    entries.sort_by_key(|e| e.file_name());
+    let mut members = Vec::new();
+    for entry in entries {
+        let path = entry.path();
+        if nested.contains(&path) {
+            continue;
+        }
+        if nested.iter().any(|n| n.starts_with(&path)) {
+            members.extend(members_around(row, &path, nested, on_dir));
+            continue;
+        }
+        let label = path
+            .strip_prefix(row)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        let member = if is_dir {
+            Some(Cache {
+                label,
+                ..stat_dir_with_progress(&path, on_dir)
+            })
+        } else {
+            stat_file(&path, label)
+        };
+        members.extend(member);
+    }
+    members
+}
+
+/// The seeds strictly inside `seed`.
+fn nested_in(seed: &Path, seeds: &[PathBuf]) -> Vec<PathBuf> {
+    seeds
+        .iter()
+        .filter(|s| s.as_path() != seed && s.starts_with(seed))
+        .cloned()
+        .collect()
+}
+
+/// Index of the outermost entry of `paths` that contains `paths[i]`, or of
+/// its first occurrence when it is listed twice. Deleting that one removes
+/// `paths[i]` as well, so it must not be deleted a second time.
+pub fn enclosing(paths: &[&Path], i: usize) -> Option<usize> {
+    (0..paths.len())
+        .filter(|&j| {
+            if paths[j] == paths[i] {
+                j < i
+            } else {
+                paths[i].starts_with(paths[j])
+            }
+        })
+        .min_by_key(|&j| (paths[j].components().count(), j))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -315,4 +461,93 @@ mod tests {
         assert_eq!(caches.len(), 1, "duplicate seed should yield one cache");
         assert_eq!(caches[0].label, "alpha");
     }
+
+    #[test]
+    fn nested_seed_shows_up_under_its_own_seed_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("sccache/0")).unwrap();
+        fs::create_dir(root.join("pip")).unwrap();
+        fs::write(root.join("sccache/0/obj"), [0u8; 100]).unwrap();
+
+        let caches = super::collect(&[root.clone(), root.join("sccache")]);
+        let paths: Vec<_> = caches.iter().map(|c| c.path.clone()).collect();
+        assert_eq!(paths.len(), 2, "{paths:?}");
+        assert!(paths.contains(&root.join("pip")));
+        assert!(paths.contains(&root.join("sccache/0")));
+    }
+
+    #[test]
+    fn deeper_nested_seed_is_left_out_of_the_parent_size() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("tools/bazel/disk/ac")).unwrap();
+        fs::write(root.join("tools/own"), [0u8; 10]).unwrap();
+        fs::write(root.join("tools/bazel/disk/ac/blob"), [0u8; 1_000]).unwrap();
+
+        let caches = super::collect(&[root.clone(), root.join("tools/bazel/disk")]);
+        let tools = caches.iter().find(|c| c.label == "tools").unwrap();
+        assert_eq!(tools.size_bytes, 10);
+        let ac = caches.iter().find(|c| c.label == "ac").unwrap();
+        assert_eq!(ac.size_bytes, 1_000);
+    }
+
+    #[test]
+    fn row_around_a_nested_seed_deletes_only_what_is_beside_it() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("tools/bazel/disk/ac")).unwrap();
+        fs::create_dir_all(root.join("tools/bazel/install")).unwrap();
+        fs::write(root.join("tools/own"), [0u8; 10]).unwrap();
+        fs::write(root.join("tools/bazel/install/bin"), [0u8; 20]).unwrap();
+        fs::write(root.join("tools/bazel/disk/ac/blob"), [0u8; 1_000]).unwrap();
+        fs::create_dir_all(root.join("only/disk")).unwrap();
+
+        let seeds = [
+            root.clone(),
+            root.join("tools/bazel/disk"),
+            root.join("only/disk"),
+        ];
+        let caches = super::collect(&seeds);
+        let tools = caches.iter().find(|c| c.label == "tools").unwrap();
+        let members: Vec<_> = tools.members.iter().map(|m| m.path.clone()).collect();
+        assert_eq!(
+            members,
+            [root.join("tools/bazel/install"), root.join("tools/own")]
+        );
+        assert_eq!(tools.members[0].label, "bazel/install");
+        assert_eq!(
+            tools.members.iter().map(|m| m.size_bytes).sum::<u64>(),
+            tools.size_bytes
+        );
+        assert!(
+            caches.iter().all(|c| c.label != "only"),
+            "a row holding nothing but a seed has nothing to delete"
+        );
+    }
+
+    #[test]
+    fn enclosing_finds_the_outermost_marked_ancestor() {
+        let paths = [
+            Path::new("/c/tools/bazel/disk/ac"),
+            Path::new("/c/tools"),
+            Path::new("/c/tools/bazel"),
+            Path::new("/c/pip"),
+        ];
+        assert_eq!(super::enclosing(&paths, 0), Some(1));
+        assert_eq!(super::enclosing(&paths, 2), Some(1));
+        assert_eq!(super::enclosing(&paths, 1), None);
+        assert_eq!(super::enclosing(&paths, 3), None);
+    }
+
+    #[test]
+    fn enclosing_points_duplicates_at_the_first_one() {
+        let paths = [
+            Path::new("/c/src/serde-1.0.100"),
+            Path::new("/c/src/log-0.4.0"),
+            Path::new("/c/src/serde-1.0.100"),
+        ];
+        assert_eq!(super::enclosing(&paths, 0), None);
+        assert_eq!(super::enclosing(&paths, 2), Some(0));
+    }
 }
@@ -3,7 +3,10 @@
 //! back into the loop on completion.
 
 use super::Msg;
+use crate::caches::cargo_gc::Referenced;
+use crate::caches::cargo_last_use::LastUse;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
 #[derive(Debug, Clone)]
@@ -12,10 +15,17 @@ pub enum Effect {
     SpawnScan {
         parent_label: String,
         parent_path: PathBuf,
+        /// Adds cargo's "unreferenced" row to registry folders.
+        cargo_refs: Option<Arc<Referenced>>,
+        /// Replaces mtimes with cargo's last-use times.
+        cargo_last_use: Option<Arc<LastUse>>,
     },
 
     /// Re-stat a single cache directory. On completion: `Msg::RefreshCompleted`.
-    SpawnRefresh { path: PathBuf },
+    SpawnRefresh {
+        path: PathBuf,
+        cargo_last_use: Option<Arc<LastUse>>,
+    },
 
     /// Delete the given items (real or dry-run). On completion: `Msg::DeleteCompleted`.
     SpawnDelete {
@@ -30,6 +40,12 @@ pub enum Effect {
     /// On completion: `Msg::SeedsLoaded`.  Dispatched once at startup so the
     /// TUI is already drawn (with a spinner) while this work happens.
     LoadSeeds { seeds: Vec<PathBuf> },
+
+    /// Stat a fixed list of `(label, path)` folders, one row each, keeping
+    /// the given labels.  On completion: `Msg::SeedsLoaded`.  Used by
+    /// `putzen --tui`, whose rows are the artifact folders themselves
+    /// rather than the children of a seed.
+    LoadFolders { folders: Vec<(String, PathBuf)> },
 }
 
 #[cfg(test)]
@@ -41,9 +57,12 @@ mod tests {
         let _ = Effect::SpawnScan {
             parent_label: "x".into(),
             parent_path: PathBuf::from("/x"),
+            cargo_refs: None,
+            cargo_last_use: None,
         };
         let _ = Effect::SpawnRefresh {
             path: PathBuf::from("/x"),
+            cargo_last_use: None,
         };
         let _ = Effect::SpawnDelete {
             items: vec![],
@@ -53,5 +72,8 @@ mod tests {
             dur: Duration::from_millis(0),
             msg: Msg::Tick,
         };
+        let _ = Effect::LoadFolders {
+            folders: vec![("x".into(), PathBuf::from("/x"))],
+        };
     }
 }
@@ -40,6 +40,8 @@ pub enum Msg {
         freed: u64,
         deleted_count: usize,
         failed_count: usize,
+        /// Read-only folders made writable so they could be deleted.
+        fixed_permissions: usize,
         deleted_indices: Vec<usize>,
     },
     /// Top-level seed scan finished — replaces the empty initial list.
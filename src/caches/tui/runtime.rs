@@ -47,6 +47,8 @@ impl EffectRunner {
             Effect::SpawnScan {
                 parent_label,
                 parent_path,
+                cargo_refs,
+                cargo_last_use,
             } => {
                 std::thread::spawn(move || {
                     // Same throttled folder-count stream as LoadSeeds so the
@@ -54,15 +56,33 @@ impl EffectRunner {
                     const PROGRESS_EVERY: usize = 200;
                     let mut total = 0usize;
                     let progress_tx = tx.clone();
-                    let children = crate::caches::scan::enumerate_seed_with_progress(
-                        &parent_path,
-                        &mut || {
-                            total += 1;
-                            if total.is_multiple_of(PROGRESS_EVERY) {
-                                let _ = progress_tx.send(Msg::ScanProgress { folders: total });
-                            }
-                        },
-                    );
+                    // A Hugging Face repo folder lists its revisions, an
+                    // Ollama store its models, instead of their folders.
+                    let mut children = crate::caches::hf_hub::repo_rows(&parent_path)
+                        .or_else(|| crate::caches::ollama::model_rows(&parent_path))
+                        .unwrap_or_else(|| {
+                            crate::caches::scan::enumerate_seed_with_progress(
+                                &parent_path,
+                                &mut || {
+                                    total += 1;
+                                    if total.is_multiple_of(PROGRESS_EVERY) {
+                                        let _ =
+                                            progress_tx.send(Msg::ScanProgress { folders: total });
+                                    }
+                                },
+                            )
+                        });
+                    crate::caches::hf_hub::relabel(&mut children);
+                    let last_use = cargo_last_use.as_deref();
+                    if let Some(last_use) = last_use {
+                        last_use.apply(&mut children);
+                    }
+                    if let Some(row) = cargo_refs
+                        .as_deref()
+                        .and_then(|refs| refs.unreferenced_row(&parent_path, last_use))
+                    {
+                        children.push(row);
+                    }
                     let _ = tx.send(Msg::ScanProgress { folders: total });
                     let _ = tx.send(Msg::ScanCompleted {
                         parent_label,
@@ -71,9 +91,15 @@ impl EffectRunner {
                     });
                 });
             }
-            Effect::SpawnRefresh { path } => {
+            Effect::SpawnRefresh {
+                path,
+                cargo_last_use,
+            } => {
                 std::thread::spawn(move || {
-                    let cache = crate::caches::scan::stat_dir(&path);
+                    let mut cache = crate::caches::scan::stat_dir(&path);
+                    if let Some(last_use) = cargo_last_use {
+                        last_use.apply(std::slice::from_mut(&mut cache));
+                    }
                     let _ = tx.send(Msg::RefreshCompleted { path, cache });
                 });
             }
@@ -88,27 +114,48 @@ impl EffectRunner {
                     let mut freed = 0u64;
                     let mut deleted_count = 0usize;
                     let mut failed_count = 0usize;
+                    let mut fixed_permissions = 0usize;
                     let mut deleted_indices: Vec<usize> = Vec::new();
-                    for (idx, path, size) in &items {
-                        match cleaner.do_cleanup(path) {
-                            Ok(Clean::Cleaned) => {
-                                freed += *size;
-                                deleted_count += 1;
-                                deleted_indices.push(*idx);
+                    // A marked row inside another marked row (a nested seed)
+                    // goes with the outer one instead of being deleted twice.
+                    let paths: Vec<&std::path::Path> =
+                        items.iter().map(|(_, p, _)| p.as_path()).collect();
+                    let outer: Vec<Option<usize>> = (0..items.len())
+                        .map(|n| crate::caches::scan::enclosing(&paths, n))
+                        .collect();
+                    // Per item: `Some(removed)` once handled, `None` on failure.
+                    let mut handled: Vec<Option<bool>> = vec![None; items.len()];
+                    for (n, (_, path, _)) in items.iter().enumerate() {
+                        if outer[n].is_some() {
+                            continue;
+                        }
+                        handled[n] = match cleaner.do_cleanup(path) {
+                            Ok(Clean::Cleaned) => Some(true),
+                            Ok(Clean::CleanedAfterChmod(fixed)) => {
+                                fixed_permissions += fixed;
+                                Some(true)
                             }
-                            Ok(Clean::NotCleaned) => {
+                            Ok(Clean::NotCleaned) => Some(false),
+                            Err(_) => None,
+                        };
+                    }
+                    for (n, (idx, _, size)) in items.iter().enumerate() {
+                        match outer[n].map_or(handled[n], |o| handled[o]) {
+                            Some(removed) => {
                                 freed += *size;
                                 deleted_count += 1;
+                                if removed {
+                                    deleted_indices.push(*idx);
+                                }
                             }
-                            Err(_) => {
-                                failed_count += 1;
-                            }
+                            None => failed_count += 1,
                         }
                     }
                     let _ = tx.send(Msg::DeleteCompleted {
                         freed,
                         deleted_count,
                         failed_count,
+                        fixed_permissions,
                         deleted_indices,
                     });
                 });
@@ -138,6 +185,31 @@ impl EffectRunner {
                     let _ = tx.send(Msg::SeedsLoaded { caches });
                 });
             }
+            Effect::LoadFolders { folders } => {
+                std::thread::spawn(move || {
+                    // Same throttled folder-count stream as LoadSeeds.
+                    const PROGRESS_EVERY: usize = 200;
+                    let mut total = 0usize;
+                    let progress_tx = tx.clone();
+                    let mut on_dir = || {
+                        total += 1;
+                        if total.is_multiple_of(PROGRESS_EVERY) {
+                            let _ = progress_tx.send(Msg::ScanProgress { folders: total });
+                        }
+                    };
+                    let caches = folders
+                        .into_iter()
+                        .map(|(label, path)| {
+                            let mut cache =
+                                crate::caches::scan::stat_dir_with_progress(&path, &mut on_dir);
+                            cache.label = label;
+                            cache
+                        })
+                        .collect();
+                    let _ = tx.send(Msg::ScanProgress { folders: total });
+                    let _ = tx.send(Msg::SeedsLoaded { caches });
+                });
+            }
         }
     }
 }
@@ -1,8 +1,11 @@
 //! TUI application state.
 
 use super::filter::Filter;
-use crate::caches::model::{Cache, FloorPolicy, MarkSet, Sort};
+use crate::caches::cargo_gc::Referenced;
+use crate::caches::cargo_last_use::LastUse;
+use crate::caches::model::{Cache, FloorPolicy, MarkSet, SeedInfo, Sort};
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::SystemTime;
 
 /// Frames of the loading spinner glyph, advanced once per event-loop idle tick.
@@ -20,6 +23,8 @@ pub struct RunOutcome {
     pub deleted: usize,
     /// Items the cleaner returned an `Err` for. `0` on dry runs.
     pub failed: usize,
+    /// Read-only folders the cleaner made writable to delete them.
+    pub fixed_permissions: usize,
     pub dry_run: bool,
 }
 
@@ -61,6 +66,9 @@ pub enum Modal {
 
 pub struct State {
     pub now: SystemTime,
+    /// Left pane title, e.g. `putzen caches — ranked`. The drill-in
+    /// breadcrumb is appended to it.
+    pub title: String,
     pub all: Vec<Cache>,
     pub sort: Sort,
     pub marks: MarkSet,
@@ -98,9 +106,26 @@ pub struct State {
     /// current cursor; on `drill_out` we restore it (then clamp), so the
     /// user lands back on the row they were on instead of at the top.
     pub cursor_stack: Vec<usize>,
+    /// Risk and note of the catalogue seeds; empty outside `putzen caches`.
+    pub seeds: Vec<SeedInfo>,
+    /// Lockfile references from `--project-root`; when set, drilling into a
+    /// cargo registry folder adds an "unreferenced" row.
+    pub cargo_refs: Option<Arc<Referenced>>,
+    /// Cargo's own record of when it last used registry and git entries;
+    /// overrides mtimes for the rows below the cargo folders.
+    pub cargo_last_use: Option<Arc<LastUse>>,
 }
 
 impl State {
+    /// The innermost seed `path` lies in, so drilled-in rows keep the
+    /// note of the cache they belong to.
+    pub fn seed_of(&self, path: &std::path::Path) -> Option<&SeedInfo> {
+        self.seeds
+            .iter()
+            .filter(|s| path.starts_with(&s.path))
+            .max_by_key(|s| s.path.components().count())
+    }
+
     pub fn sorted_indices(&self) -> Vec<usize> {
         let mut idx: Vec<usize> = (0..self.all.len()).collect();
         if let Some(f) = &self.filter {
@@ -5,6 +5,7 @@ use super::effect::Effect;
 use super::filter::Filter;
 use super::msg::Msg;
 use super::state::{Loading, Modal, Overlay, RunOutcome, State};
+use crate::caches::model::Cache;
 
 /// Pure state-transition function.  Each arm returns the next `State` and a
 /// `Command` that the runtime drains (synchronous events re-fed through
@@ -120,17 +121,29 @@ pub fn update(mut state: State, msg: Msg) -> (State, Command<Effect, Msg>) {
             };
             let parent_label = state.all[idx].label.clone();
             let parent_path = state.all[idx].path.clone();
+            // A virtual row already holds its children; no IO needed.
+            if !state.all[idx].members.is_empty() {
+                let members = state.all[idx].members.clone();
+                state.stack_labels.push(parent_label);
+                state.drill_paths.push(parent_path);
+                state.drill_into(members);
+                return (state, Command::done());
+            }
             state.loading = Some(Loading {
                 label: format!("scanning {parent_label}"),
                 frame: 0,
                 started: std::time::Instant::now(),
                 folders: Some(0),
             });
+            let cargo_refs = state.cargo_refs.clone();
+            let cargo_last_use = state.cargo_last_use.clone();
             (
                 state,
                 Command::effect(Effect::SpawnScan {
                     parent_label,
                     parent_path,
+                    cargo_refs,
+                    cargo_last_use,
                 }),
             )
         }
@@ -171,7 +184,31 @@ pub fn update(mut state: State, msg: Msg) -> (State, Command<Effect, Msg>) {
                 return (state, Command::done());
             }
             let was_dirty = state.level_dirty;
+            let left = if was_dirty {
+                state.all.clone()
+            } else {
+                Vec::new()
+            };
             let popped_path = state.drill_out_with_path();
+            // Leaving a virtual row after deleting some of its members:
+            // rebuild it from the members that are left, nothing to re-stat.
+            let virtual_slot = popped_path.as_ref().and_then(|path| {
+                state
+                    .all
+                    .iter()
+                    .position(|c| &c.path == path && !c.members.is_empty())
+            });
+            if let (true, Some(i)) = (was_dirty, virtual_slot) {
+                if left.is_empty() {
+                    state.all.remove(i);
+                } else {
+                    let row = &state.all[i];
+                    state.all[i] = Cache::virtual_row(row.label.clone(), row.path.clone(), left);
+                }
+                state.clamp_cursor_to_visible();
+                state.level_dirty = true;
+                return (state, Command::done());
+            }
             if was_dirty {
                 if let Some(path) = popped_path {
                     let path_label = path
@@ -189,14 +226,25 @@ pub fn update(mut state: State, msg: Msg) -> (State, Command<Effect, Msg>) {
                     // exposed, so any level above it is now stale too.  Set
                     // before returning so the next DrillOut sees it.
                     state.level_dirty = true;
-                    return (state, Command::effect(Effect::SpawnRefresh { path }));
+                    let cargo_last_use = state.cargo_last_use.clone();
+                    return (
+                        state,
+                        Command::effect(Effect::SpawnRefresh {
+                            path,
+                            cargo_last_use,
+                        }),
+                    );
                 }
             }
             (state, Command::done())
         }
         Msg::RefreshCompleted { path, cache } => {
+            // Keep the label: analyzers name rows other than by folder name.
             if let Some(slot) = state.all.iter_mut().find(|c| c.path == path) {
-                *slot = cache;
+                *slot = Cache {
+                    label: std::mem::take(&mut slot.label),
+                    ..cache
+                };
             }
             state.loading = None;
             (state, Command::done())
@@ -226,11 +274,22 @@ pub fn update(mut state: State, msg: Msg) -> (State, Command<Effect, Msg>) {
             (state, Command::done())
         }
         Msg::ConfirmDelete => {
+            // A virtual row deletes its members, all under the row's index.
             let to_delete: Vec<(usize, std::path::PathBuf, u64)> = state
                 .marks
                 .marked
                 .iter()
-                .filter_map(|&i| state.all.get(i).map(|c| (i, c.path.clone(), c.size_bytes)))
+                .filter_map(|&i| state.all.get(i).map(|c| (i, c)))
+                .flat_map(|(i, c)| {
+                    if c.members.is_empty() {
+                        vec![(i, c.path.clone(), c.size_bytes)]
+                    } else {
+                        c.members
+                            .iter()
+                            .map(|m| (i, m.path.clone(), m.size_bytes))
+                            .collect()
+                    }
+                })
                 .collect();
             state.modal = Modal::None;
             state.marks.clear();
@@ -260,6 +319,7 @@ pub fn update(mut state: State, msg: Msg) -> (State, Command<Effect, Msg>) {
             freed,
             deleted_count,
             failed_count,
+            fixed_permissions,
             deleted_indices,
         } => {
             state.total_freed += freed;
@@ -269,6 +329,7 @@ pub fn update(mut state: State, msg: Msg) -> (State, Command<Effect, Msg>) {
             if !state.dry_run {
                 let mut idxs = deleted_indices;
                 idxs.sort_unstable_by(|a, b| b.cmp(a));
+                idxs.dedup();
                 for i in idxs {
                     if i < state.all.len() {
                         state.all.remove(i);
@@ -284,6 +345,7 @@ pub fn update(mut state: State, msg: Msg) -> (State, Command<Effect, Msg>) {
                     freed,
                     deleted: deleted_count,
                     failed: failed_count,
+                    fixed_permissions,
                     dry_run: state.dry_run,
                 },
             });
@@ -400,12 +462,14 @@ mod tests {
             dir_count: 0,
             top_files: Vec::new(),
             unreadable: 0,
+            members: Vec::new(),
         }
     }
 
     fn state(items: Vec<Cache>) -> State {
         State {
             now: SystemTime::UNIX_EPOCH + Duration::from_secs(10_000_000),
+            title: "putzen caches — ranked".into(),
             all: items,
             sort: Sort::Score,
             marks: MarkSet::default(),
@@ -428,6 +492,9 @@ mod tests {
             level_dirty: false,
             drill_paths: Vec::new(),
             cursor_stack: Vec::new(),
+            seeds: Vec::new(),
+            cargo_refs: None,
+            cargo_last_use: None,
         }
     }
 
@@ -694,6 +761,88 @@ mod tests {
         ));
     }
 
+    fn unreferenced(members: Vec<Cache>) -> Cache {
+        crate::caches::cargo_gc::virtual_row(std::path::Path::new("/x/index"), members)
+    }
+
+    #[test]
+    fn drill_into_virtual_row_shows_members_without_scanning() {
+        let row = unreferenced(vec![cache("a-1.0.0", 1, 0), cache("b-2.0.0", 2, 0)]);
+        let s = state(vec![row]);
+        let (s, cmd) = update(s, Msg::DrillIn);
+        assert!(cmd.is_done(), "members are already known");
+        assert!(s.loading.is_none());
+        assert_eq!(s.stack_labels, ["unreferenced"]);
+        assert_eq!(s.all.len(), 2);
+    }
+
+    #[test]
+    fn confirm_delete_expands_virtual_row_into_its_members() {
+        let row = unreferenced(vec![cache("a-1.0.0", 1, 0), cache("b-2.0.0", 2, 0)]);
+        let mut s = state(vec![row]);
+        s.marks.marked.insert(0);
+        let (_, cmd) = update(s, Msg::ConfirmDelete);
+        let [Effect::SpawnDelete { items, .. }] = cmd.effects.as_slice() else {
+            panic!("expected SpawnDelete, got {:?}", cmd.effects);
+        };
+        assert_eq!(
+            items,
+            &[
+                (0, PathBuf::from("/x/a-1.0.0"), 1),
+                (0, PathBuf::from("/x/b-2.0.0"), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn confirm_delete_of_a_row_around_a_nested_seed_spares_the_seed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("tools/bazel/disk/ac")).unwrap();
+        std::fs::write(root.join("tools/own"), [0u8; 10]).unwrap();
+        std::fs::write(root.join("tools/bazel/disk/ac/blob"), [0u8; 1_000]).unwrap();
+        let rows = crate::caches::scan::collect(&[root.clone(), root.join("tools/bazel/disk")]);
+        let tools = rows.into_iter().find(|c| c.label == "tools").unwrap();
+
+        let mut s = state(vec![tools]);
+        s.marks.marked.insert(0);
+        let (_, cmd) = update(s, Msg::ConfirmDelete);
+        let [Effect::SpawnDelete { items, .. }] = cmd.effects.as_slice() else {
+            panic!("expected SpawnDelete, got {:?}", cmd.effects);
+        };
+        assert_eq!(items, &[(0, root.join("tools/own"), 10)]);
+    }
+
+    #[test]
+    fn drill_out_of_virtual_row_after_delete_rebuilds_it() {
+        let row = unreferenced(vec![cache("a-1.0.0", 1, 0), cache("b-2.0.0", 2, 0)]);
+        // The virtual row is the bigger one, so it ranks first.
+        let s = state(vec![cache("serde-1.0.0", 1, 0), row]);
+        let (mut s, _) = update(s, Msg::DrillIn);
+        assert_eq!(s.stack_labels, ["unreferenced"]);
+        s.dry_run = false;
+        let gone = s.all.iter().position(|c| c.label == "a-1.0.0").unwrap();
+        let (s, _) = update(
+            s,
+            Msg::DeleteCompleted {
+                freed: 1,
+                deleted_count: 1,
+                failed_count: 0,
+                fixed_permissions: 0,
+                deleted_indices: vec![gone],
+            },
+        );
+        let (s, cmd) = update(s, Msg::DrillOut);
+        assert!(
+            cmd.is_done(),
+            "a virtual row has nothing on disk to re-stat"
+        );
+        let row = s.all.iter().find(|c| c.label == "unreferenced").unwrap();
+        assert_eq!(row.size_bytes, 2);
+        assert_eq!(row.members.len(), 1);
+        assert!(s.level_dirty, "the parent folder shrank too");
+    }
+
     #[test]
     fn delete_completed_real_run_removes_rows_and_accumulates_freed() {
         let mut s = state(vec![cache("a", 1, 0), cache("b", 2, 0), cache("c", 3, 0)]);
@@ -705,6 +854,7 @@ mod tests {
                 freed: 5,
                 deleted_count: 2,
                 failed_count: 0,
+                fixed_permissions: 0,
                 deleted_indices: vec![0, 2],
             },
         );
@@ -736,6 +886,7 @@ mod tests {
                 freed: 3,
                 deleted_count: 2,
                 failed_count: 0,
+                fixed_permissions: 0,
                 deleted_indices: vec![0, 1],
             },
         );
@@ -773,6 +924,7 @@ mod tests {
                 freed: 1,
                 deleted_count: 1,
                 failed_count: 0,
+                fixed_permissions: 0,
                 deleted_indices: vec![1],
             },
         );
@@ -791,6 +943,7 @@ mod tests {
                 freed: 1,
                 deleted_count: 1,
                 failed_count: 1,
+                fixed_permissions: 0,
                 deleted_indices: vec![0],
             },
         );
@@ -809,6 +962,7 @@ mod tests {
                 freed: 100,
                 deleted_count: 1,
                 failed_count: 0,
+                fixed_permissions: 0,
                 deleted_indices: vec![0],
             },
         );
@@ -828,6 +982,7 @@ mod tests {
                 freed: 1,
                 deleted: 1,
                 failed: 0,
+                fixed_permissions: 0,
                 dry_run: false,
             },
         });
@@ -912,6 +1067,7 @@ mod tests {
             dir_count: 0,
             top_files: Vec::new(),
             unreadable: 0,
+            members: Vec::new(),
         }]);
         let children = crate::caches::scan::enumerate_seed(&cache);
         s.drill_into(children);
@@ -1099,6 +1255,7 @@ mod tests {
                 },
             ],
             unreadable: 0,
+            members: Vec::new(),
         }]);
         let (s, c) = update(s, Msg::ToggleFocus);
         assert!(c.is_done());
@@ -1276,6 +1433,7 @@ mod tests {
                 freed: 60,
                 deleted_count: 1,
                 failed_count: 0,
+                fixed_permissions: 0,
                 deleted_indices: vec![0],
             },
         );
@@ -1290,7 +1448,7 @@ mod tests {
         //    original parent path so the top-level npm row gets re-stat'd.
         let (s, cmd) = update(s, Msg::DrillOut);
         match cmd.effects.as_slice() {
-            [Effect::SpawnRefresh { path }] => {
+            [Effect::SpawnRefresh { path, .. }] => {
                 assert_eq!(*path, parent_path, "refresh target must be /x/npm");
             }
             other => panic!("expected SpawnRefresh, got {other:?}"),
@@ -1308,6 +1466,7 @@ mod tests {
             dir_count: 0,
             top_files: Vec::new(),
             unreadable: 0,
+            members: Vec::new(),
         };
         let (s, _) = update(
             s,
@@ -1344,7 +1503,7 @@ mod tests {
         let (mut s, cmd) = update(s, Msg::DrillOut);
         assert!(matches!(
             cmd.effects.as_slice(),
-            [Effect::SpawnRefresh { path }] if path == &std::path::PathBuf::from("/x/npm/registry")
+            [Effect::SpawnRefresh { path, .. }] if path == &std::path::PathBuf::from("/x/npm/registry")
         ));
         assert!(s.level_dirty, "L1 inherits dirtiness from the propagation");
         assert!(s.loading.is_some());
@@ -1355,7 +1514,7 @@ mod tests {
         let (s, cmd) = update(s, Msg::DrillOut);
         assert!(matches!(
             cmd.effects.as_slice(),
-            [Effect::SpawnRefresh { path }] if path == &std::path::PathBuf::from("/x/npm")
+            [Effect::SpawnRefresh { path, .. }] if path == &std::path::PathBuf::from("/x/npm")
         ));
         assert!(s.loading.is_some());
     }
@@ -1580,6 +1739,7 @@ mod tests {
             dir_count: 0,
             top_files: Vec::new(),
             unreadable: 0,
+            members: Vec::new(),
         };
         let (s, cmd) = update(
             s,
@@ -1593,6 +1753,23 @@ mod tests {
         assert!(cmd.is_done());
     }
 
+    #[test]
+    fn refresh_completed_keeps_the_row_label() {
+        let mut repo = cache("models--acme--tiny", 100, 0);
+        repo.label = "acme/tiny".into();
+        let s = state(vec![repo]);
+        let path = s.all[0].path.clone();
+        let (s, _) = update(
+            s,
+            Msg::RefreshCompleted {
+                cache: cache("models--acme--tiny", 40, 0),
+                path,
+            },
+        );
+        assert_eq!(s.all[0].label, "acme/tiny");
+        assert_eq!(s.all[0].size_bytes, 40);
+    }
+
     #[test]
     fn refresh_completed_unknown_path_clears_loading() {
         let mut s = state(vec![cache("a", 100, 0)]);
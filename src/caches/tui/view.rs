@@ -153,6 +153,13 @@ pub fn draw_result(outcome: &RunOutcome, area: Rect, buf: &mut Buffer) {
     } else {
         String::new()
     };
+    let chmod_line = (outcome.fixed_permissions > 0).then(|| {
+        format!(
+            "made {} read-only {} writable",
+            outcome.fixed_permissions,
+            pluralize(outcome.fixed_permissions as u64, "folder", "folders")
+        )
+    });
     let noun = pluralize(outcome.deleted as u64, "folder", "folders");
     let line = if outcome.dry_run {
         format!(
@@ -168,25 +175,30 @@ pub fn draw_result(outcome: &RunOutcome, area: Rect, buf: &mut Buffer) {
         )
     };
 
-    Clear.render(modal, buf);
-    Paragraph::new(vec![
+    let mut lines = vec![
         Line::from(Span::raw("")),
         Line::from(Span::styled(line, body_style.add_modifier(Modifier::BOLD))),
-    ])
-    .style(body_style)
-    .alignment(Alignment::Center)
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(block_style)
-            .style(body_style)
-            .title(Span::styled(
-                title_text,
-                block_style.add_modifier(Modifier::BOLD),
-            ))
-            .title_alignment(Alignment::Center),
-    )
-    .render(modal, buf);
+    ];
+    if let Some(chmod_line) = chmod_line {
+        lines.push(Line::from(Span::raw(chmod_line)));
+    }
+
+    Clear.render(modal, buf);
+    Paragraph::new(lines)
+        .style(body_style)
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(block_style)
+                .style(body_style)
+                .title(Span::styled(
+                    title_text,
+                    block_style.add_modifier(Modifier::BOLD),
+                ))
+                .title_alignment(Alignment::Center),
+        )
+        .render(modal, buf);
 }
 
 fn render_active_mark_modal(state: &State, area: Rect, buf: &mut Buffer) {
@@ -278,10 +290,14 @@ fn render_modal(state: &State, area: Rect, buf: &mut Buffer) {
         // Few enough: list each cache and a Total row.
         for &i in state.marks.marked.iter() {
             if let Some(c) = state.all.get(i) {
-                lines.push(Line::from(vec![
+                let mut spans = vec![
                     Span::styled(format!("{}  ", c.label), body_style),
                     Span::styled(human_size(c.size_bytes), dim_style),
-                ]));
+                ];
+                if let Some(risk) = state.seed_of(&c.path).and_then(|s| s.risk) {
+                    spans.push(Span::styled(format!("  {risk}"), THEME.risk_style(risk)));
+                }
+                lines.push(Line::from(spans));
             }
         }
         lines.push(Line::from(Span::raw("")));
@@ -301,6 +317,19 @@ fn render_modal(state: &State, area: Rect, buf: &mut Buffer) {
             ),
             Span::styled(human_size(total), body_style.add_modifier(Modifier::BOLD)),
         ]));
+        let highest = state
+            .marks
+            .marked
+            .iter()
+            .filter_map(|&i| state.all.get(i))
+            .filter_map(|c| state.seed_of(&c.path).and_then(|s| s.risk))
+            .max();
+        if let Some(risk) = highest {
+            lines.push(Line::from(Span::styled(
+                format!("up to {risk}"),
+                THEME.risk_style(risk),
+            )));
+        }
     }
 
     lines.push(Line::from(Span::raw("")));
@@ -357,6 +386,10 @@ fn render_modal(state: &State, area: Rect, buf: &mut Buffer) {
         .render(modal, buf);
 }
 
+fn spans_width(spans: &[Span]) -> usize {
+    spans.iter().map(|s| s.content.chars().count()).sum()
+}
+
 /// Width of the right-side padding inside the left panel block, kept here
 /// so `col_widths` and the actual `Block::padding(...)` stay in sync.
 pub(super) const LEFT_PANEL_RIGHT_PAD: u16 = 1;
@@ -510,12 +543,9 @@ fn render_left(state: &mut State, area: Rect, buf: &mut Buffer) {
         .collect();
 
     let title = if state.stack_labels.is_empty() {
-        " putzen caches — ranked ".to_string()
+        format!(" {} ", state.title)
     } else {
-        format!(
-            " putzen caches — ranked — {} ",
-            state.stack_labels.join(" > ")
-        )
+        format!(" {} — {} ", state.title, state.stack_labels.join(" > "))
     };
 
     // Draw the block + borders + title first, then split the inner area into
@@ -732,6 +762,32 @@ fn render_right(state: &mut State, area: Rect, buf: &mut Buffer) {
     let mut header_lines = vec![
         Line::from(Span::styled(c.label.clone(), THEME.title_style())),
         Line::from(Span::styled(path_display, dim_style)),
+    ];
+    // What deleting it costs, from the seed catalogue. One line, so the
+    // header height stays exact.
+    if let Some(seed) = state.seed_of(&c.path) {
+        let mut spans = Vec::new();
+        if let Some(risk) = seed.risk {
+            spans.push(Span::styled(risk.to_string(), THEME.risk_style(risk)));
+        }
+        if let Some(tool) = &seed.tool {
+            let sep = if spans.is_empty() { "" } else { " · " };
+            spans.push(Span::styled(format!("{sep}filled by {tool}"), body_style));
+        }
+        if let Some(note) = &seed.note {
+            let sep = if spans.is_empty() { "" } else { " · " };
+            let room =
+                (inner.width as usize).saturating_sub(spans_width(&spans) + sep.chars().count());
+            spans.push(Span::styled(
+                format!("{sep}{}", truncate_with_ellipsis(note, room)),
+                body_style,
+            ));
+        }
+        if !spans.is_empty() {
+            header_lines.push(Line::from(spans));
+        }
+    }
+    header_lines.extend([
         Line::from(Span::raw("")),
         Line::from(vec![
             Span::styled("Size         ", dim_style),
@@ -757,7 +813,7 @@ fn render_right(state: &mut State, area: Rect, buf: &mut Buffer) {
             Span::styled("Last touched ", dim_style),
             Span::styled(touched, body_style),
         ]),
-    ];
+    ]);
 
     if c.unreadable > 0 {
         header_lines.push(Line::from(Span::styled(
@@ -904,6 +960,7 @@ mod tests {
         let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 86_400);
         State {
             now,
+            title: "putzen caches — ranked".into(),
             all: vec![
                 Cache {
                     label: "alpha".into(),
@@ -914,6 +971,7 @@ mod tests {
                     dir_count: 0,
                     top_files: Vec::new(),
                     unreadable: 0,
+                    members: Vec::new(),
                 },
                 Cache {
                     label: "beta".into(),
@@ -924,6 +982,7 @@ mod tests {
                     dir_count: 1,
                     top_files: Vec::new(),
                     unreadable: 0,
+                    members: Vec::new(),
                 },
             ],
             sort: Sort::Score,
@@ -947,6 +1006,9 @@ mod tests {
             level_dirty: false,
             drill_paths: Vec::new(),
             cursor_stack: Vec::new(),
+            seeds: Vec::new(),
+            cargo_refs: None,
+            cargo_last_use: None,
         }
     }
 
@@ -1103,6 +1165,44 @@ mod tests {
         assert!(dump.contains("blob.bin"), "biggest file missing");
     }
 
+    fn with_seed(mut state: State) -> State {
+        state.seeds = vec![crate::caches::model::SeedInfo {
+            path: PathBuf::from("/x"),
+            label: "x cache".into(),
+            risk: Some(crate::caches::defaults::Risk::High),
+            tool: Some("bazel".into()),
+            note: Some("offline builds break".into()),
+        }];
+        state
+    }
+
+    #[test]
+    fn right_pane_shows_seed_note() {
+        let backend = TestBackend::new(160, 30);
+        let mut term = Terminal::new(backend).unwrap();
+        let mut state = with_seed(fixture());
+        term.draw(|f| render(&mut state, f.area(), f.buffer_mut()))
+            .unwrap();
+        let dump = buffer_to_string(term.backend().buffer());
+        assert!(
+            dump.contains("high risk · filled by bazel · offline build"),
+            "note missing:\n{dump}"
+        );
+    }
+
+    #[test]
+    fn modal_shows_risk_tier() {
+        let backend = TestBackend::new(80, 20);
+        let mut term = Terminal::new(backend).unwrap();
+        let mut state = with_seed(fixture());
+        state.marks.toggle(0);
+        state.modal = crate::caches::tui::Modal::DeleteConfirm;
+        term.draw(|f| render(&mut state, f.area(), f.buffer_mut()))
+            .unwrap();
+        let dump = buffer_to_string(term.backend().buffer());
+        assert!(dump.contains("high risk"), "risk tier missing:\n{dump}");
+    }
+
     #[test]
     fn right_pane_shows_partial_footnote() {
         let backend = TestBackend::new(120, 30);
@@ -1127,6 +1227,7 @@ mod tests {
             freed: 1_500_000_000,
             deleted: 3,
             failed: 0,
+            fixed_permissions: 0,
             dry_run: false,
         };
         term.draw(|f| draw_result(&outcome, f.area(), f.buffer_mut()))
@@ -1148,6 +1249,7 @@ mod tests {
             freed: 1_000,
             deleted: 2,
             failed: 1,
+            fixed_permissions: 0,
             dry_run: false,
         };
         term.draw(|f| draw_result(&outcome, f.area(), f.buffer_mut()))
@@ -1160,6 +1262,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn draw_result_reports_fixed_permissions() {
+        let backend = TestBackend::new(80, 20);
+        let mut term = Terminal::new(backend).unwrap();
+        let outcome = RunOutcome {
+            freed: 1_000,
+            deleted: 1,
+            failed: 0,
+            fixed_permissions: 12,
+            dry_run: false,
+        };
+        term.draw(|f| draw_result(&outcome, f.area(), f.buffer_mut()))
+            .unwrap();
+        let dump = buffer_to_string(term.backend().buffer());
+        assert!(
+            dump.contains("made 12 read-only folders writable"),
+            "chmod line missing:\n{}",
+            dump
+        );
+    }
+
     #[test]
     fn draw_result_dry_run_shows_would_free() {
         let backend = TestBackend::new(80, 20);
@@ -1168,6 +1291,7 @@ mod tests {
             freed: 1_000,
             deleted: 1,
             failed: 0,
+            fixed_permissions: 0,
             dry_run: true,
         };
         term.draw(|f| draw_result(&outcome, f.area(), f.buffer_mut()))
@@ -1384,6 +1508,7 @@ mod tests {
                 dir_count: 0,
                 top_files: Vec::new(),
                 unreadable: 0,
+                members: Vec::new(),
             })
             .collect();
 
@@ -1,5 +1,6 @@
 //! Theme + small reusable rendering helpers.
 
+use crate::caches::defaults::Risk;
 use ratatui::style::{Color, Modifier, Style};
 
 pub struct Theme {
@@ -66,6 +67,14 @@ impl Theme {
     pub fn dim_style(&self) -> Style {
         Style::default().fg(self.dim)
     }
+    /// Same `ok` → `warm` → `hot` scale as the score bar.
+    pub fn risk_style(&self, risk: Risk) -> Style {
+        match risk {
+            Risk::Low => Style::default().fg(self.ok),
+            Risk::Medium => Style::default().fg(self.warm),
+            Risk::High => Style::default().fg(self.hot).add_modifier(Modifier::BOLD),
+        }
+    }
 
     /// Smooth heat-map between `ok`, `warm`, and `hot` keyed by `t ∈ [0, 1]`:
     /// `0.0` is pure `ok` (low score), `0.5` is pure `warm`, `1.0` is pure
@@ -0,0 +1,113 @@
+//! `putzen check`: a CI gate that fails when the artifacts under a root
+//! add up to more than a budget. Never deletes anything.
+
+use crate::caches::format::tildify;
+use crate::{FileToFolderMatch, Folder, HumanReadable, IsFolderToRemove};
+use std::path::{Path, PathBuf};
+
+/// The sized artifact folders of a root, largest first.
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    pub folders: Vec<(PathBuf, u64)>,
+}
+
+impl CheckReport {
+    /// Size every folder its rule still matches.
+    pub fn measure(folders: impl Iterator<Item = (Folder, &'static FileToFolderMatch)>) -> Self {
+        let mut folders: Vec<(PathBuf, u64)> = folders
+            .filter(|(folder, rule)| rule.is_folder_to_remove(folder))
+            .map(|(folder, _)| {
+                let size = folder.calculate_size() as u64;
+                (folder.as_ref().to_path_buf(), size)
+            })
+            .collect();
+        folders.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Self { folders }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.folders.iter().map(|(_, size)| size).sum()
+    }
+
+    pub fn is_within(&self, budget: u64) -> bool {
+        self.total() <= budget
+    }
+
+    /// One verdict line, followed by the `top` largest folders when over
+    /// budget.
+    pub fn render(&self, budget: u64, top: usize, home: Option<&Path>) -> String {
+        let verdict = if self.is_within(budget) {
+            "within budget"
+        } else {
+            "OVER BUDGET"
+        };
+        let mut out = format!(
+            "{} reclaimable in {} folders, budget is {}: {verdict}\n",
+            (self.total() as usize).as_human_readable(),
+            self.folders.len(),
+            (budget as usize).as_human_readable()
+        );
+        if !self.is_within(budget) {
+            for (path, size) in self.folders.iter().take(top) {
+                out.push_str(&format!(
+                    "{:>10}  {}\n",
+                    (*size as usize).as_human_readable(),
+                    tildify(path, home)
+                ));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    static RULE: FileToFolderMatch = FileToFolderMatch::new("Cargo.toml", "target");
+
+    fn project(root: &Path, name: &str, bytes: usize, with_manifest: bool) -> Folder {
+        let target = root.join(name).join("target");
+        std::fs::create_dir_all(&target).unwrap();
+        if with_manifest {
+            std::fs::write(root.join(name).join("Cargo.toml"), "").unwrap();
+        }
+        std::fs::write(target.join("artefact"), vec![0u8; bytes]).unwrap();
+        Folder::try_from(target).unwrap()
+    }
+
+    #[test]
+    fn sums_matching_folders_largest_first() {
+        let tmp = tempfile::tempdir().unwrap();
+        let folders = vec![
+            (project(tmp.path(), "small", 100, true), &RULE),
+            (project(tmp.path(), "big", 300, true), &RULE),
+            (project(tmp.path(), "no-manifest", 5000, false), &RULE),
+        ];
+        let report = CheckReport::measure(folders.into_iter());
+        assert_eq!(report.total(), 400);
+        assert!(report.folders[0].0.ends_with("big/target"));
+        assert!(report.is_within(400));
+        assert!(!report.is_within(399));
+    }
+
+    #[test]
+    fn render_lists_the_largest_only_when_over_budget() {
+        let report = CheckReport {
+            folders: vec![
+                (PathBuf::from("/u/sven/a/target"), 3 << 20),
+                (PathBuf::from("/u/sven/b/target"), 1 << 20),
+            ],
+        };
+        let home = Some(Path::new("/u/sven"));
+        let over = report.render(2 << 20, 1, home);
+        assert_eq!(
+            over,
+            "4.0MiB reclaimable in 2 folders, budget is 2.0MiB: OVER BUDGET\n    3.0MiB  ~/a/target\n"
+        );
+        let within = report.render(8 << 20, 1, home);
+        assert_eq!(within.lines().count(), 1);
+        assert!(within.ends_with("within budget\n"));
+    }
+}
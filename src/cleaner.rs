@@ -4,11 +4,13 @@ use remove_dir_all::remove_dir_all;
 #[cfg(not(target_family = "windows"))]
 use std::fs::remove_dir_all;
 
-use std::io::Result;
+use std::io::{ErrorKind, Result};
 use std::path::Path;
 
 pub enum Clean {
     Cleaned,
+    /// Cleaned, after making this many read-only folders inside writable.
+    CleanedAfterChmod(usize),
     NotCleaned,
 }
 
@@ -19,11 +21,67 @@ pub trait DoCleanUp {
 #[derive(Default)]
 pub struct ProperCleaner;
 impl DoCleanUp for ProperCleaner {
+    /// Read-only trees (e.g. the Go module cache) fail with a permission
+    /// error; then the folders we own inside are made writable and the
+    /// removal is retried once.
     fn do_cleanup(&self, path_to_remove: &Path) -> Result<Clean> {
-        remove_dir_all(path_to_remove).map(|_| Clean::Cleaned)
+        // Cache analyzers hand over single files too, e.g. `.crate`s.
+        if !std::fs::symlink_metadata(path_to_remove)?.is_dir() {
+            return std::fs::remove_file(path_to_remove).map(|_| Clean::Cleaned);
+        }
+        match remove_dir_all(path_to_remove) {
+            Ok(()) => Ok(Clean::Cleaned),
+            Err(e) if e.kind() == ErrorKind::PermissionDenied => {
+                let fixed = make_owned_dirs_writable(path_to_remove)?;
+                if fixed == 0 {
+                    return Err(e);
+                }
+                remove_dir_all(path_to_remove).map(|_| Clean::CleanedAfterChmod(fixed))
+            }
+            Err(e) => Err(e),
+        }
     }
 }
 
+/// Give the owner read, write and search permission on every folder in
+/// `root` (itself included) that the effective user owns and lacks one of
+/// them. Symlinks are never followed, so nothing outside `root` is touched.
+/// Returns how many folders were changed.
+#[cfg(unix)]
+fn make_owned_dirs_writable(root: &Path) -> Result<usize> {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    let euid = unsafe { libc::geteuid() };
+    let mut fixed = 0;
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let meta = std::fs::symlink_metadata(&dir)?;
+        if !meta.is_dir() {
+            continue;
+        }
+        let mode = meta.permissions().mode();
+        if meta.uid() == euid && mode & 0o700 != 0o700 {
+            std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(mode | 0o700))?;
+            fixed += 1;
+        }
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                dirs.push(entry.path());
+            }
+        }
+    }
+    Ok(fixed)
+}
+
+#[cfg(not(unix))]
+fn make_owned_dirs_writable(_root: &Path) -> Result<usize> {
+    Ok(0)
+}
+
 #[derive(Default)]
 pub struct DryRunCleaner;
 impl DoCleanUp for DryRunCleaner {
@@ -32,3 +90,59 @@ impl DoCleanUp for DryRunCleaner {
         Ok(Clean::NotCleaned)
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn mode(path: &Path) -> u32 {
+        std::fs::metadata(path).unwrap().permissions().mode() & 0o777
+    }
+
+    fn read_only(path: &Path) {
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o555)).unwrap();
+    }
+
+    #[test]
+    fn fixes_read_only_folders_inside_the_target_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let target = tmp.path().join("mod");
+        let nested = target.join("pkg@v1.0.0").join("internal");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join("x.go"), "package x").unwrap();
+        let outside = tmp.path().join("outside");
+        std::fs::create_dir(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, target.join("link")).unwrap();
+        read_only(&outside);
+        read_only(&nested);
+        read_only(nested.parent().unwrap());
+        read_only(&target);
+
+        assert_eq!(make_owned_dirs_writable(&target).unwrap(), 3);
+        assert_eq!(mode(&target), 0o755);
+        assert_eq!(mode(&nested), 0o755);
+        assert_eq!(mode(&outside), 0o555, "symlinks are not followed");
+        assert_eq!(make_owned_dirs_writable(&target).unwrap(), 0);
+
+        read_only(&nested);
+        assert!(matches!(
+            ProperCleaner.do_cleanup(&target),
+            Ok(Clean::Cleaned | Clean::CleanedAfterChmod(_))
+        ));
+        assert!(!target.exists());
+        assert!(outside.exists());
+    }
+
+    #[test]
+    fn removes_single_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("serde-1.0.200.crate");
+        std::fs::write(&file, "crate").unwrap();
+        assert!(matches!(
+            ProperCleaner.do_cleanup(&file),
+            Ok(Clean::Cleaned)
+        ));
+        assert!(!file.exists());
+    }
+}
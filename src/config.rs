@@ -0,0 +1,380 @@
+//! Defaults from `~/.config/putzen/config.toml` and the environment.
+//!
+//! ```toml
+//! [main]
+//! hidden = [".worktrees", ".jj"]
+//! dry_run = false
+//! parallelism = 8
+//!
+//! [caches]
+//! floor = "14d"
+//! dry_run = true
+//! ```
+//!
+//! Every value is resolved with the precedence CLI > env > config file >
+//! built-in default, and remembers where it came from so that
+//! `putzen config show` can tell.
+
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Hidden directories the walker enters when nothing else is configured.
+pub const DEFAULT_HIDDEN_GLOB: &str = ".worktrees";
+/// Threads of the artifact walk.
+pub const DEFAULT_PARALLELISM: usize = 8;
+/// Caches younger than this are flagged ACTIVE in `putzen caches`.
+pub const DEFAULT_FLOOR: &str = "7d";
+
+/// Points to another config file, mostly for tests and CI.
+pub const ENV_CONFIG: &str = "PUTZEN_CONFIG";
+/// Whitespace separated, e.g. `".worktrees .jj"`. Empty skips every hidden dir.
+pub const ENV_HIDDEN: &str = "PUTZEN_HIDDEN";
+pub const ENV_DRY_RUN: &str = "PUTZEN_DRY_RUN";
+pub const ENV_PARALLELISM: &str = "PUTZEN_PARALLELISM";
+pub const ENV_CACHES_FLOOR: &str = "PUTZEN_CACHES_FLOOR";
+pub const ENV_CACHES_DRY_RUN: &str = "PUTZEN_CACHES_DRY_RUN";
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    pub main: MainSection,
+    pub caches: CachesSection,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MainSection {
+    pub hidden: Option<Vec<String>>,
+    pub dry_run: Option<bool>,
+    pub parallelism: Option<usize>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CachesSection {
+    pub floor: Option<String>,
+    pub dry_run: Option<bool>,
+}
+
+impl ConfigFile {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| e.to_string())
+    }
+
+    /// Read `path`; a missing file is an empty config.
+    pub fn load_from(path: &Path) -> io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {e}", path.display()),
+                )
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// `$PUTZEN_CONFIG`, or `config.toml` next to the highscores.
+pub fn config_path() -> Option<PathBuf> {
+    std::env::var_os(ENV_CONFIG)
+        .map(PathBuf::from)
+        .or_else(|| dirs_lite::config_dir().map(|d| d.join("putzen").join("config.toml")))
+}
+
+/// Where an effective value came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    Cli,
+    Env(&'static str),
+    Config,
+    Default,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Cli => write!(f, "command line"),
+            Source::Env(var) => write!(f, "env {var}"),
+            Source::Config => write!(f, "config file"),
+            Source::Default => write!(f, "built-in default"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+/// Values given on the command line; `None` when the flag was absent.
+#[derive(Debug, Default)]
+pub struct CliValues {
+    pub hidden: Option<Vec<String>>,
+    pub dry_run: Option<bool>,
+    pub parallelism: Option<usize>,
+    pub caches_floor: Option<String>,
+    pub caches_dry_run: Option<bool>,
+}
+
+/// The effective configuration.
+#[derive(Debug)]
+pub struct Settings {
+    pub hidden: Setting<Vec<String>>,
+    pub dry_run: Setting<bool>,
+    pub parallelism: Setting<usize>,
+    pub caches_floor: Setting<String>,
+    pub caches_dry_run: Setting<bool>,
+}
+
+impl Settings {
+    /// Resolve against the real environment and config file.
+    pub fn load(cli: CliValues) -> io::Result<Self> {
+        let file = match config_path() {
+            Some(path) => ConfigFile::load_from(&path)?,
+            None => ConfigFile::default(),
+        };
+        Self::resolve(cli, |var| std::env::var(var).ok(), file)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+    }
+
+    pub fn resolve(
+        cli: CliValues,
+        env: impl Fn(&str) -> Option<String>,
+        file: ConfigFile,
+    ) -> Result<Self, String> {
+        let hidden_env = env(ENV_HIDDEN).map(|v| v.split_whitespace().map(String::from).collect());
+        Ok(Self {
+            hidden: pick(
+                cli.hidden,
+                ENV_HIDDEN,
+                hidden_env,
+                file.main.hidden,
+                vec![DEFAULT_HIDDEN_GLOB.to_string()],
+            ),
+            dry_run: pick(
+                cli.dry_run,
+                ENV_DRY_RUN,
+                env_parsed(&env, ENV_DRY_RUN, parse_bool)?,
+                file.main.dry_run,
+                false,
+            ),
+            parallelism: pick(
+                cli.parallelism,
+                ENV_PARALLELISM,
+                env_parsed(&env, ENV_PARALLELISM, |v| v.parse().ok())?,
+                file.main.parallelism,
+                DEFAULT_PARALLELISM,
+            ),
+            caches_floor: pick(
+                cli.caches_floor,
+                ENV_CACHES_FLOOR,
+                env(ENV_CACHES_FLOOR),
+                file.caches.floor,
+                DEFAULT_FLOOR.to_string(),
+            ),
+            caches_dry_run: pick(
+                cli.caches_dry_run,
+                ENV_CACHES_DRY_RUN,
+                env_parsed(&env, ENV_CACHES_DRY_RUN, parse_bool)?,
+                file.caches.dry_run,
+                false,
+            ),
+        })
+    }
+
+    /// The merged config in TOML syntax, each value annotated with its source.
+    pub fn render(&self) -> String {
+        let hidden = self
+            .hidden
+            .value
+            .iter()
+            .map(|g| format!("{g:?}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let lines = [
+            ("[main]".to_string(), None),
+            (format!("hidden = [{hidden}]"), Some(self.hidden.source)),
+            (
+                format!("dry_run = {}", self.dry_run.value),
+                Some(self.dry_run.source),
+            ),
+            (
+                format!("parallelism = {}", self.parallelism.value),
+                Some(self.parallelism.source),
+            ),
+            (String::new(), None),
+            ("[caches]".to_string(), None),
+            (
+                format!("floor = {:?}", self.caches_floor.value),
+                Some(self.caches_floor.source),
+            ),
+            (
+                format!("dry_run = {}", self.caches_dry_run.value),
+                Some(self.caches_dry_run.source),
+            ),
+        ];
+        let width = lines.iter().map(|(l, _)| l.len()).max().unwrap_or(0);
+        lines
+            .iter()
+            .map(|(line, source)| match source {
+                Some(source) => format!("{line:<width$}  # {source}\n"),
+                None => format!("{line}\n"),
+            })
+            .collect()
+    }
+}
+
+fn pick<T>(
+    cli: Option<T>,
+    env_var: &'static str,
+    env: Option<T>,
+    config: Option<T>,
+    default: T,
+) -> Setting<T> {
+    if let Some(value) = cli {
+        Setting {
+            value,
+            source: Source::Cli,
+        }
+    } else if let Some(value) = env {
+        Setting {
+            value,
+            source: Source::Env(env_var),
+        }
+    } else if let Some(value) = config {
+        Setting {
+            value,
+            source: Source::Config,
+        }
+    } else {
+        Setting {
+            value: default,
+            source: Source::Default,
+        }
+    }
+}
+
+fn env_parsed<T>(
+    env: &impl Fn(&str) -> Option<String>,
+    var: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<Option<T>, String> {
+    env(var)
+        .map(|v| parse(v.trim()).ok_or_else(|| format!("invalid value `{v}` in ${var}")))
+        .transpose()
+}
+
+fn parse_bool(v: &str) -> Option<bool> {
+    match v.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" | "" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |k| vars.get(k).cloned()
+    }
+
+    #[test]
+    fn parses_both_sections() {
+        let file = ConfigFile::parse(
+            "[main]\nhidden = [\".jj\"]\nparallelism = 2\n\n[caches]\nfloor = \"14d\"\n",
+        )
+        .unwrap();
+        assert_eq!(file.main.hidden, Some(vec![".jj".to_string()]));
+        assert_eq!(file.main.parallelism, Some(2));
+        assert_eq!(file.caches.floor.as_deref(), Some("14d"));
+        assert_eq!(file.caches.dry_run, None);
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        let err = ConfigFile::parse("[main]\nhiden = []\n").unwrap_err();
+        assert!(err.contains("hiden"), "got: {err}");
+    }
+
+    #[test]
+    fn missing_file_is_empty_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = ConfigFile::load_from(&tmp.path().join("nope.toml")).unwrap();
+        assert_eq!(file, ConfigFile::default());
+    }
+
+    #[test]
+    fn precedence_is_cli_env_config_default() {
+        let file = ConfigFile::parse(
+            "[main]\ndry_run = true\nparallelism = 2\nhidden = [\".jj\"]\n[caches]\nfloor = \"1d\"\n",
+        )
+        .unwrap();
+        let cli = CliValues {
+            parallelism: Some(16),
+            ..Default::default()
+        };
+        let s = Settings::resolve(cli, env(&[(ENV_DRY_RUN, "no")]), file).unwrap();
+
+        assert_eq!(s.parallelism.value, 16);
+        assert_eq!(s.parallelism.source, Source::Cli);
+        assert!(!s.dry_run.value);
+        assert_eq!(s.dry_run.source, Source::Env(ENV_DRY_RUN));
+        assert_eq!(s.hidden.value, [".jj"]);
+        assert_eq!(s.hidden.source, Source::Config);
+        assert_eq!(s.caches_floor.value, "1d");
+        assert!(!s.caches_dry_run.value);
+        assert_eq!(s.caches_dry_run.source, Source::Default);
+    }
+
+    #[test]
+    fn env_hidden_is_whitespace_separated() {
+        let s = Settings::resolve(
+            CliValues::default(),
+            env(&[(ENV_HIDDEN, ".worktrees  .{jj,pijul}")]),
+            ConfigFile::default(),
+        )
+        .unwrap();
+        assert_eq!(s.hidden.value, [".worktrees", ".{jj,pijul}"]);
+    }
+
+    #[test]
+    fn invalid_env_values_are_errors() {
+        let err = Settings::resolve(
+            CliValues::default(),
+            env(&[(ENV_PARALLELISM, "many")]),
+            ConfigFile::default(),
+        )
+        .unwrap_err();
+        assert!(err.contains(ENV_PARALLELISM), "got: {err}");
+    }
+
+    #[test]
+    fn render_names_every_source() {
+        let s = Settings::resolve(
+            CliValues {
+                caches_floor: Some("2w".into()),
+                ..Default::default()
+            },
+            env(&[]),
+            ConfigFile::default(),
+        )
+        .unwrap();
+        let text = s.render();
+        assert!(text.contains("hidden = [\".worktrees\"]"), "got:\n{text}");
+        assert!(text.contains("# built-in default"), "got:\n{text}");
+        assert!(text.contains("floor = \"2w\""), "got:\n{text}");
+        assert!(text.contains("# command line"), "got:\n{text}");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::report::OutputFormat;
 use dialoguer::theme::ColorfulTheme;
 use dialoguer::Confirm;
 use std::io::Result;
@@ -15,11 +16,18 @@ pub struct DecisionContext {
     pub is_dry_run: bool,
     pub yes_to_all: bool,
     pub working_dir: PathBuf,
+    pub format: OutputFormat,
 }
 
 impl DecisionContext {
+    /// Print a line for humans. In the JSON formats stdout belongs to the
+    /// report, so the line goes to stderr instead.
     pub fn println(&self, msg: impl AsRef<str>) {
-        println!("{}", msg.as_ref());
+        if self.format.is_machine_readable() {
+            eprintln!("{}", msg.as_ref());
+        } else {
+            println!("{}", msg.as_ref());
+        }
     }
 }
 
@@ -61,3 +69,20 @@ impl Decide for NiceInteractiveDecider {
         }))
     }
 }
+
+/// Answers every question with yes, because the decision was already made
+/// up front — e.g. in the plan edited via `--edit`.
+#[derive(Default)]
+pub struct PlannedDecider;
+
+impl Decide for PlannedDecider {
+    fn obtain_decision(
+        &mut self,
+        ctx: &DecisionContext,
+        question: impl AsRef<str>,
+    ) -> Result<Decision> {
+        let suffix = if ctx.is_dry_run { " [dry-run]" } else { "" };
+        ctx.println(format!("  {}{suffix} [yes by plan]", question.as_ref()));
+        Ok(Decision::Yes)
+    }
+}
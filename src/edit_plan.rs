@@ -0,0 +1,274 @@
+//! `putzen --edit` — review the deletion plan in `$EDITOR`, git-rebase-style.
+//!
+//! After the scan every candidate is written as one line,
+//!
+//! ```text
+//! delete 3.2GiB ~/src/foo/target  # rust, 94d old
+//! ```
+//!
+//! and the edited file is parsed back. Lines switched to `keep` (or
+//! removed) are skipped. Only paths offered in the plan can be deleted, so a
+//! typo can never widen the blast radius.
+
+use crate::caches::format::tildify;
+use crate::HumanReadable;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const HELP: &str = "\
+#
+# Commands:
+# d, delete <size> <path> = delete this folder
+# k, keep <size> <path> = keep this folder
+#
+# Lines can be switched between `delete` and `keep`.
+# Removing a line keeps the folder as well.
+# Everything after `  #` is a comment and ignored.
+";
+
+/// One folder offered for deletion in the plan.
+pub struct PlanCandidate {
+    pub path: PathBuf,
+    pub size: usize,
+    /// Name of the ecosystem of the matching rule, e.g. `rust`.
+    pub ecosystem: String,
+    /// Time since the newest file inside was modified. `None` when empty.
+    pub age: Option<Duration>,
+}
+
+/// A malformed plan line. `line` is 1-based, like in an editor.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PlanError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for PlanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "plan line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for PlanError {}
+
+impl From<PlanError> for io::Error {
+    fn from(e: PlanError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    }
+}
+
+/// Render the plan file, every candidate pre-set to `delete`.
+pub fn render(candidates: &[PlanCandidate], home: Option<&Path>) -> String {
+    let mut out = String::new();
+    for c in candidates {
+        let age = c
+            .age
+            .map(|a| format!("{}d old", a.as_secs() / 86_400))
+            .unwrap_or_else(|| "empty".into());
+        out.push_str(&format!(
+            "delete {} {}  # {}, {age}\n",
+            c.size.as_human_readable(),
+            tildify(&c.path, home),
+            c.ecosystem,
+        ));
+    }
+    out.push_str(HELP);
+    out
+}
+
+/// Parse an edited plan and return the paths marked `delete`, in plan order.
+pub fn parse(
+    text: &str,
+    candidates: &[PlanCandidate],
+    home: Option<&Path>,
+) -> Result<Vec<PathBuf>, PlanError> {
+    let known: HashSet<&Path> = candidates.iter().map(|c| c.path.as_path()).collect();
+    let mut seen = HashSet::new();
+    let mut to_delete = Vec::new();
+
+    for (i, raw) in text.lines().enumerate() {
+        let line = i + 1;
+        let err = |message: String| PlanError { line, message };
+
+        let content = raw.split_once("  #").map_or(raw, |(head, _)| head).trim();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+
+        let (action, rest) = split_word(content);
+        let delete = match action {
+            "d" | "delete" => true,
+            "k" | "keep" => false,
+            other => return Err(err(format!("unknown command `{other}`"))),
+        };
+        let (size, path) = split_word(rest);
+        if size.is_empty() || path.is_empty() {
+            return Err(err(format!("expected `{action} <size> <path>`")));
+        }
+
+        let path = expand_tilde(path, home);
+        if !known.contains(path.as_path()) {
+            return Err(err(format!("`{}` is not part of the plan", path.display())));
+        }
+        if !seen.insert(path.clone()) {
+            return Err(err(format!("`{}` is listed twice", path.display())));
+        }
+        if delete {
+            to_delete.push(path);
+        }
+    }
+
+    Ok(to_delete)
+}
+
+/// Write `plan` to a temp file, open it in `$VISUAL` / `$EDITOR` (falling
+/// back to `vi`), wait for the editor to exit and return the edited text.
+/// The file is created fresh with owner-only permissions, so nobody else on
+/// a shared `/tmp` can plant or swap the plan that decides what is deleted.
+pub fn edit(plan: &str) -> io::Result<String> {
+    let mut tmp = tempfile::Builder::new()
+        .prefix("putzen-plan-")
+        .suffix(".txt")
+        .tempfile()?;
+    io::Write::write_all(&mut tmp, plan.as_bytes())?;
+    let file = tmp.path().to_path_buf();
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".into());
+    // Editors are often configured with arguments, e.g. `code --wait`.
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(&file)
+        .status();
+
+    let edited = match status {
+        Ok(s) if s.success() => std::fs::read_to_string(&file),
+        Ok(s) => Err(io::Error::other(format!(
+            "editor `{editor}` exited with {s}"
+        ))),
+        Err(e) => Err(io::Error::new(
+            e.kind(),
+            format!("failed to launch editor `{editor}`: {e}"),
+        )),
+    };
+    drop(tmp);
+    edited
+}
+
+/// Split off the first whitespace-delimited word; the rest is trimmed.
+fn split_word(s: &str) -> (&str, &str) {
+    match s.split_once(char::is_whitespace) {
+        Some((head, rest)) => (head, rest.trim()),
+        None => (s, ""),
+    }
+}
+
+fn expand_tilde(raw: &str, home: Option<&Path>) -> PathBuf {
+    match (raw.strip_prefix("~/"), home) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ if raw == "~" => home.map(Path::to_path_buf).unwrap_or_else(|| raw.into()),
+        _ => PathBuf::from(raw),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates() -> Vec<PlanCandidate> {
+        vec![
+            PlanCandidate {
+                path: PathBuf::from("/u/sven/src/foo/target"),
+                size: 3 * 1024 * 1024 * 1024 + 200 * 1024 * 1024,
+                ecosystem: "rust".into(),
+                age: Some(Duration::from_secs(94 * 86_400)),
+            },
+            PlanCandidate {
+                path: PathBuf::from("/srv/my app/node_modules"),
+                size: 1024,
+                ecosystem: "javascript".into(),
+                age: None,
+            },
+        ]
+    }
+
+    fn home() -> PathBuf {
+        PathBuf::from("/u/sven")
+    }
+
+    #[test]
+    fn render_lists_every_candidate_as_delete() {
+        let text = render(&candidates(), Some(&home()));
+        let mut lines = text.lines();
+        assert_eq!(
+            lines.next(),
+            Some("delete 3.2GiB ~/src/foo/target  # rust, 94d old")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("delete 1.0KiB /srv/my app/node_modules  # javascript, empty")
+        );
+    }
+
+    #[test]
+    fn untouched_plan_deletes_everything() {
+        let c = candidates();
+        let text = render(&c, Some(&home()));
+        let paths = parse(&text, &c, Some(&home())).unwrap();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("/u/sven/src/foo/target"),
+                PathBuf::from("/srv/my app/node_modules")
+            ]
+        );
+    }
+
+    #[test]
+    fn keep_and_removed_lines_are_skipped() {
+        let c = candidates();
+        let text = "keep 3.2GiB ~/src/foo/target  # rust, 94d old\n";
+        assert!(parse(text, &c, Some(&home())).unwrap().is_empty());
+
+        let text = "d 1.0KiB /srv/my app/node_modules\n";
+        assert_eq!(
+            parse(text, &c, Some(&home())).unwrap(),
+            [PathBuf::from("/srv/my app/node_modules")]
+        );
+    }
+
+    #[test]
+    fn unknown_command_reports_line_number() {
+        let c = candidates();
+        let text = "# comment\n\nnuke 3.2GiB ~/src/foo/target\n";
+        let err = parse(text, &c, Some(&home())).unwrap_err();
+        assert_eq!(err.line, 3);
+        assert!(err.to_string().contains("plan line 3"), "got: {err}");
+    }
+
+    #[test]
+    fn missing_path_is_malformed() {
+        let err = parse("delete 3.2GiB\n", &candidates(), Some(&home())).unwrap_err();
+        assert_eq!(err.line, 1);
+        assert!(err.message.contains("<path>"), "got: {err}");
+    }
+
+    #[test]
+    fn paths_outside_the_plan_are_rejected() {
+        let err = parse("delete 1B /etc\n", &candidates(), Some(&home())).unwrap_err();
+        assert!(err.message.contains("not part of the plan"), "got: {err}");
+    }
+
+    #[test]
+    fn duplicate_paths_are_rejected() {
+        let text = "delete 1B ~/src/foo/target\nkeep 1B ~/src/foo/target\n";
+        let err = parse(text, &candidates(), Some(&home())).unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+}
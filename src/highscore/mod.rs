@@ -91,6 +91,16 @@ pub struct HighscoreObserver {
 impl HighscoreObserver {
     /// Load highscores from disk or create a new empty set.
     pub fn load() -> std::io::Result<Self> {
+        Self::load_announcing(true)
+    }
+
+    /// Like [`Self::load`], but without the first-run message on stdout,
+    /// for runs whose stdout is machine-readable.
+    pub fn load_quietly() -> std::io::Result<Self> {
+        Self::load_announcing(false)
+    }
+
+    fn load_announcing(announce_first_run: bool) -> std::io::Result<Self> {
         let file_path = highscores_path()?;
         let (highscores, is_first_run) = if file_path.exists() {
             let content = fs::read_to_string(&file_path)?;
@@ -101,7 +111,7 @@ impl HighscoreObserver {
             (Highscores::default(), true)
         };
 
-        if is_first_run {
+        if is_first_run && announce_first_run {
             println!("\u{1F3C6} A wild cleaner appears! Highscore board initialized.");
         }
 
@@ -0,0 +1,44 @@
+//! The deletion journal: an append-only log of what unattended runs (like
+//! `putzen watch`) deleted, one JSON event per line in the same shape as
+//! `--format ndjson`.
+
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const ENV_JOURNAL: &str = "PUTZEN_JOURNAL";
+
+/// `$PUTZEN_JOURNAL`, else `journal.ndjson` in putzen's data directory
+/// (e.g. `~/.local/share/putzen/`).
+pub fn default_path() -> Option<PathBuf> {
+    std::env::var_os(ENV_JOURNAL)
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs_lite::data_dir().map(|d| d.join("putzen").join("journal.ndjson")))
+}
+
+/// Open the journal for appending, creating it and its folder if needed.
+pub fn open(path: &Path) -> io::Result<File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn open_creates_folders_and_appends() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("state").join("journal.ndjson");
+        writeln!(open(&path).unwrap(), "{{\"n\":1}}").unwrap();
+        writeln!(open(&path).unwrap(), "{{\"n\":2}}").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\"n\":1}\n{\"n\":2}\n"
+        );
+    }
+}
@@ -12,16 +12,20 @@ pub use crate::cleaner::*;
 pub use crate::decider::*;
 pub use crate::observer::*;
 
+use crate::report::{Event, EventKind};
+
 use jwalk::{ClientState, DirEntry, Parallelism};
 use std::convert::{TryFrom, TryInto};
 use std::fmt::{Display, Formatter};
 use std::io::{Error, ErrorKind, Result};
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
+#[derive(Debug)]
 pub struct FileToFolderMatch {
     file_to_check: &'static str,
     folder_to_remove: &'static str,
+    ecosystem: &'static str,
 }
 
 pub enum FolderProcessed {
@@ -40,9 +44,29 @@ impl FileToFolderMatch {
         Self {
             file_to_check,
             folder_to_remove,
+            ecosystem: folder_to_remove,
         }
     }
 
+    /// names the ecosystem (e.g. `rust`) this rule belongs to, used for display only.
+    /// Defaults to the name of the folder to remove.
+    pub const fn with_ecosystem(mut self, ecosystem: &'static str) -> Self {
+        self.ecosystem = ecosystem;
+        self
+    }
+
+    pub fn file_to_check(&self) -> &'static str {
+        self.file_to_check
+    }
+
+    pub fn folder_to_remove(&self) -> &'static str {
+        self.folder_to_remove
+    }
+
+    pub fn ecosystem(&self) -> &'static str {
+        self.ecosystem
+    }
+
     /// builds the absolut path, that is to be removed, in the given folder
     pub fn path_to_remove(&self, folder: impl AsRef<Path>) -> Option<impl AsRef<Path>> {
         folder
@@ -56,6 +80,14 @@ impl FileToFolderMatch {
 #[derive(Debug, Eq, PartialEq, Hash)]
 pub struct Folder(PathBuf);
 
+/// Size and newest modification time of everything below a folder.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FolderStats {
+    pub size: usize,
+    /// `None` when the folder holds no files.
+    pub newest_mtime: Option<SystemTime>,
+}
+
 impl Folder {
     pub fn accept(
         &self,
@@ -70,8 +102,18 @@ impl Folder {
             return Ok(FolderProcessed::NoRuleMatch);
         }
 
-        let size_amount = self.calculate_size();
+        let stats = self.stats();
+        let size_amount = stats.size;
         let size = size_amount.as_human_readable();
+        let path = self.to_string();
+        let event = |kind| Event::new(ctx.is_dry_run, kind);
+        observer.on_event(&event(EventKind::CandidateFound {
+            path: path.clone(),
+            rule: rule.into(),
+            size_bytes: size_amount as u64,
+            newest_mtime: stats.newest_mtime.and_then(report::rfc3339),
+        }));
+
         let folder = self.as_ref().display().to_string();
         let folder = ctx
             .working_dir
@@ -87,27 +129,69 @@ impl Folder {
             });
 
         ctx.println(format!("Cleaning {folder} with {size}"));
-        ctx.println(format!(
-            "  ├─ because of {}",
-            PathBuf::from("..").join(rule.file_to_check).display()
-        ));
-
-        let result = match decider.obtain_decision(ctx, "├─ delete directory recursively?") {
-            Ok(Decision::Yes) => match cleaner.do_cleanup(self.as_ref())? {
-                Clean::Cleaned => {
+        let because = PathBuf::from("..").join(rule.file_to_check);
+        if rule.ecosystem() == crate::project_config::EXTRA_ECOSYSTEM {
+            ctx.println(format!(
+                "  ├─ listed in `extra_targets` of {}",
+                because.display()
+            ));
+        } else {
+            ctx.println(format!("  ├─ because of {}", because.display()));
+        }
+
+        let decision = decider.obtain_decision(ctx, "├─ delete directory recursively?");
+        observer.on_event(&event(EventKind::Decision {
+            path: path.clone(),
+            rule: rule.into(),
+            decision: match decision {
+                Ok(Decision::Yes) => "yes",
+                Ok(Decision::Quit) => "quit",
+                _ => "no",
+            },
+        }));
+
+        let result = match decision {
+            Ok(Decision::Yes) => match cleaner.do_cleanup(self.as_ref()) {
+                Ok(clean @ (Clean::Cleaned | Clean::CleanedAfterChmod(_))) => {
+                    if let Clean::CleanedAfterChmod(fixed) = clean {
+                        ctx.println(format!(
+                            "  ├─ made {fixed} read-only folder(s) inside writable to delete them"
+                        ));
+                    }
+                    observer.on_event(&event(EventKind::Cleaned {
+                        path,
+                        rule: rule.into(),
+                        size_bytes: size_amount as u64,
+                    }));
                     if let Some(hint) = observer.on_folder_cleaned(size_amount as u64) {
                         ctx.println(format!("  ├─ {hint}"));
                     }
                     ctx.println(format!("  └─ deleted {size}"));
                     FolderProcessed::Cleaned(size_amount)
                 }
-                Clean::NotCleaned => {
+                Ok(Clean::NotCleaned) => {
+                    if ctx.is_dry_run {
+                        observer.on_event(&event(EventKind::Cleaned {
+                            path,
+                            rule: rule.into(),
+                            size_bytes: size_amount as u64,
+                        }));
+                    }
                     ctx.println(format!(
                         "  └─ not deleted{}{size}",
                         if ctx.is_dry_run { " [dry-run] " } else { "" }
                     ));
                     FolderProcessed::Skipped
                 }
+                Err(error) => {
+                    observer.on_event(&event(EventKind::Failed {
+                        path,
+                        rule: rule.into(),
+                        size_bytes: size_amount as u64,
+                        error: error.to_string(),
+                    }));
+                    return Err(error);
+                }
             },
             Ok(Decision::Quit) => {
                 ctx.println("  └─ quiting");
@@ -122,8 +206,15 @@ impl Folder {
         Ok(result)
     }
 
-    fn calculate_size(&self) -> usize {
-        jwalk::WalkDirGeneric::<((), Option<usize>)>::new(self.as_ref())
+    /// Total size in bytes of all files below the folder.
+    pub fn calculate_size(&self) -> usize {
+        self.stats().size
+    }
+
+    /// Walk the folder once, in parallel, summing file sizes and tracking
+    /// the newest file modification time.
+    pub fn stats(&self) -> FolderStats {
+        jwalk::WalkDirGeneric::<((), Option<FolderStats>)>::new(self.as_ref())
             .skip_hidden(false)
             .follow_links(false)
             .parallelism(Parallelism::RayonDefaultPool {
@@ -133,12 +224,14 @@ impl Folder {
                 dir_entry_results.iter_mut().for_each(|dir_entry_result| {
                     if let Ok(dir_entry) = dir_entry_result {
                         if !dir_entry.file_type.is_dir() {
-                            dir_entry.client_state = Some(
-                                dir_entry
-                                    .metadata()
+                            let metadata = dir_entry.metadata().ok();
+                            dir_entry.client_state = Some(FolderStats {
+                                size: metadata
+                                    .as_ref()
                                     .map(|m| m.len() as usize)
                                     .unwrap_or_default(),
-                            );
+                                newest_mtime: metadata.and_then(|m| m.modified().ok()),
+                            });
                         }
                     }
                 })
@@ -146,7 +239,10 @@ impl Folder {
             .into_iter()
             .filter_map(|f| f.ok())
             .filter_map(|e| e.client_state)
-            .sum()
+            .fold(FolderStats::default(), |acc, file| FolderStats {
+                size: acc.size + file.size,
+                newest_mtime: acc.newest_mtime.max(file.newest_mtime),
+            })
     }
 }
 
@@ -288,3 +384,18 @@ mod tests {
 }
 
 pub mod caches;
+pub mod check;
+pub mod config;
+pub mod edit_plan;
+pub mod journal;
+pub mod list;
+pub mod metrics;
+pub mod mounts;
+pub mod ownership;
+pub mod plan_file;
+pub mod project_config;
+pub mod projects;
+pub mod reclaim;
+pub mod report;
+pub mod summary;
+pub mod watch;
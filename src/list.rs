@@ -0,0 +1,212 @@
+//! `putzen list` — an artifact `du` for the tree: size every candidate and
+//! print a sorted table. Never prompts, never deletes.
+
+use crate::caches::format::{human_age, human_date, tildify};
+use crate::{FileToFolderMatch, Folder, FolderStats, HumanReadable};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListSort {
+    /// Largest first.
+    #[default]
+    Size,
+    /// Oldest first; empty folders last.
+    Age,
+    Path,
+}
+
+impl FromStr for ListSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "size" => Ok(ListSort::Size),
+            "age" => Ok(ListSort::Age),
+            "path" => Ok(ListSort::Path),
+            other => Err(format!(
+                "unknown sort `{other}`, expected `size`, `age` or `path`"
+            )),
+        }
+    }
+}
+
+/// One sized artifact folder.
+#[derive(Debug, Clone)]
+pub struct ListEntry {
+    pub path: PathBuf,
+    pub ecosystem: &'static str,
+    pub stats: FolderStats,
+}
+
+impl ListEntry {
+    /// Time since the newest file inside was written. `None` when empty.
+    pub fn age(&self, now: SystemTime) -> Option<Duration> {
+        self.stats
+            .newest_mtime
+            .map(|t| now.duration_since(t).unwrap_or_default())
+    }
+}
+
+/// Size every folder. The folders are spread over one worker per core, so
+/// a few huge `node_modules` don't hold up the rest.
+pub fn size_all(folders: Vec<(Folder, &'static FileToFolderMatch)>) -> Vec<ListEntry> {
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(folders.len().max(1));
+    let next = AtomicUsize::new(0);
+    let entries = Mutex::new(Vec::with_capacity(folders.len()));
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some((folder, rule)) = folders.get(i) else {
+                    break;
+                };
+                let entry = ListEntry {
+                    path: folder.as_ref().to_path_buf(),
+                    ecosystem: rule.ecosystem(),
+                    stats: folder.stats(),
+                };
+                entries.lock().unwrap().push(entry);
+            });
+        }
+    });
+
+    entries.into_inner().unwrap()
+}
+
+pub fn sort(entries: &mut [ListEntry], by: ListSort) {
+    match by {
+        ListSort::Size => entries.sort_by(|a, b| {
+            b.stats
+                .size
+                .cmp(&a.stats.size)
+                .then_with(|| a.path.cmp(&b.path))
+        }),
+        // `Some` sorts after `None`, so flip to push empty folders last.
+        ListSort::Age => entries.sort_by(|a, b| {
+            match (a.stats.newest_mtime, b.stats.newest_mtime) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+            .then_with(|| a.path.cmp(&b.path))
+        }),
+        ListSort::Path => entries.sort_by(|a, b| a.path.cmp(&b.path)),
+    }
+}
+
+/// Render the table: size, age, last build, rule and path, plus a total.
+pub fn render(entries: &[ListEntry], now: SystemTime, home: Option<&Path>) -> String {
+    let mut out = format!(
+        "{:>10} {:>5} {:<10} {:<11} {}\n",
+        "size", "age", "last build", "rule", "path"
+    );
+    for e in entries {
+        let (age, built) = match e.stats.newest_mtime {
+            Some(t) => (
+                human_age(now.duration_since(t).unwrap_or_default()),
+                human_date(t),
+            ),
+            None => ("-".into(), "-".into()),
+        };
+        out.push_str(&format!(
+            "{:>10} {:>5} {:<10} {:<11} {}\n",
+            e.stats.size.as_human_readable(),
+            age,
+            built,
+            e.ecosystem,
+            tildify(&e.path, home)
+        ));
+    }
+    let total: usize = entries.iter().map(|e| e.stats.size).sum();
+    out.push_str(&format!(
+        "{:>10} in {} folders\n",
+        total.as_human_readable(),
+        entries.len()
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, size: usize, days_ago: Option<u64>, now: SystemTime) -> ListEntry {
+        ListEntry {
+            path: PathBuf::from(path),
+            ecosystem: "rust",
+            stats: FolderStats {
+                size,
+                newest_mtime: days_ago.map(|d| now - Duration::from_secs(d * 86_400)),
+            },
+        }
+    }
+
+    fn paths(entries: &[ListEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.path.to_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn sorts_by_size_age_and_path() {
+        let now = SystemTime::now();
+        let mut entries = vec![
+            entry("/b", 10, Some(1), now),
+            entry("/a", 30, None, now),
+            entry("/c", 20, Some(90), now),
+        ];
+        sort(&mut entries, ListSort::Size);
+        assert_eq!(paths(&entries), ["/a", "/c", "/b"]);
+        sort(&mut entries, ListSort::Age);
+        assert_eq!(paths(&entries), ["/c", "/b", "/a"]);
+        sort(&mut entries, ListSort::Path);
+        assert_eq!(paths(&entries), ["/a", "/b", "/c"]);
+        assert!("name".parse::<ListSort>().is_err());
+    }
+
+    #[test]
+    fn size_all_sizes_every_folder() {
+        static RULE: FileToFolderMatch = FileToFolderMatch::new("Cargo.toml", "target");
+        let tmp = tempfile::tempdir().unwrap();
+        let folders = (0..5)
+            .map(|i| {
+                let dir = tmp.path().join(format!("p{i}")).join("target");
+                std::fs::create_dir_all(&dir).unwrap();
+                std::fs::write(dir.join("artefact"), vec![0u8; 100 * (i + 1)]).unwrap();
+                (Folder::try_from(dir).unwrap(), &RULE)
+            })
+            .collect();
+
+        let mut entries = size_all(folders);
+        sort(&mut entries, ListSort::Size);
+        let sizes: Vec<usize> = entries.iter().map(|e| e.stats.size).collect();
+        assert_eq!(sizes, [500, 400, 300, 200, 100]);
+        assert!(entries.iter().all(|e| e.stats.newest_mtime.is_some()));
+    }
+
+    #[test]
+    fn render_shows_age_last_build_and_total() {
+        let now = SystemTime::now();
+        let entries = [
+            entry("/u/sven/src/foo/target", 2048, Some(94), now),
+            entry("/srv/empty/target", 0, None, now),
+        ];
+        let text = render(&entries, now, Some(Path::new("/u/sven")));
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[1].contains("3mo"), "got: {}", lines[1]);
+        assert!(
+            lines[1].ends_with("rust        ~/src/foo/target"),
+            "got: {}",
+            lines[1]
+        );
+        assert!(lines[2].contains(" - "), "got: {}", lines[2]);
+        assert_eq!(lines[3].trim(), "2.0KiB in 2 folders");
+    }
+}
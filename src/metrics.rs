@@ -0,0 +1,259 @@
+//! `putzen metrics`: reclaimable space as Prometheus gauges, in the text
+//! format node_exporter's textfile collector reads.
+
+use crate::caches::model::Cache;
+use crate::list::ListEntry;
+use crate::report::RunSummary;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// The last `summary` in the deletion journal and when it was written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastRun {
+    /// Unix seconds.
+    pub timestamp: i64,
+    pub dry_run: bool,
+    pub summary: RunSummary,
+}
+
+impl LastRun {
+    /// Find the newest `summary` event in the journal text. Lines that are
+    /// not valid JSON (e.g. a half-written last line) are skipped.
+    pub fn from_journal(text: &str) -> Option<Self> {
+        text.lines().rev().find_map(|line| {
+            let event: serde_json::Value = serde_json::from_str(line).ok()?;
+            if event["event"] != "summary" {
+                return None;
+            }
+            let timestamp: jiff::Timestamp = event["timestamp"].as_str()?.parse().ok()?;
+            Some(Self {
+                timestamp: timestamp.as_second(),
+                dry_run: event["dry_run"].as_bool().unwrap_or(false),
+                summary: serde_json::from_value(event).ok()?,
+            })
+        })
+    }
+}
+
+/// Render all gauges. `artifacts` are the sized artifact folders of the
+/// roots, `caches` the scanned cache directories.
+pub fn render(
+    artifacts: &[ListEntry],
+    caches: &[Cache],
+    last_run: Option<&LastRun>,
+    now: SystemTime,
+) -> String {
+    let mut out = String::new();
+
+    let mut ecosystems: BTreeMap<&str, (u64, usize)> = BTreeMap::new();
+    for entry in artifacts {
+        let row = ecosystems.entry(entry.ecosystem).or_default();
+        row.0 += entry.stats.size as u64;
+        row.1 += 1;
+    }
+    header(
+        &mut out,
+        "putzen_artifact_reclaimable_bytes",
+        "Bytes in build artifact folders, by ecosystem.",
+    );
+    for (ecosystem, (bytes, _)) in &ecosystems {
+        let labels = format!("ecosystem=\"{}\"", escape(ecosystem));
+        sample(
+            &mut out,
+            "putzen_artifact_reclaimable_bytes",
+            &labels,
+            *bytes,
+        );
+    }
+    header(
+        &mut out,
+        "putzen_artifact_folders",
+        "Number of build artifact folders, by ecosystem.",
+    );
+    for (ecosystem, (_, count)) in &ecosystems {
+        let labels = format!("ecosystem=\"{}\"", escape(ecosystem));
+        sample(&mut out, "putzen_artifact_folders", &labels, *count as u64);
+    }
+
+    let oldest = artifacts
+        .iter()
+        .filter_map(|e| e.age(now))
+        .max()
+        .unwrap_or_default();
+    header(
+        &mut out,
+        "putzen_oldest_artifact_age_seconds",
+        "Age of the newest file in the oldest artifact folder.",
+    );
+    sample(
+        &mut out,
+        "putzen_oldest_artifact_age_seconds",
+        "",
+        oldest.as_secs(),
+    );
+
+    header(
+        &mut out,
+        "putzen_cache_reclaimable_bytes",
+        "Bytes in user-level cache directories, by cache.",
+    );
+    for cache in caches {
+        let labels = format!(
+            "label=\"{}\",path=\"{}\"",
+            escape(&cache.label),
+            escape(&cache.path.to_string_lossy())
+        );
+        sample(
+            &mut out,
+            "putzen_cache_reclaimable_bytes",
+            &labels,
+            cache.size_bytes,
+        );
+    }
+
+    if let Some(run) = last_run {
+        let counters: [(&str, &str, u64); 5] = [
+            (
+                "putzen_last_run_timestamp_seconds",
+                "When the last journaled cleanup finished.",
+                run.timestamp.max(0) as u64,
+            ),
+            (
+                "putzen_last_run_found",
+                "Folders offered in the last journaled cleanup.",
+                run.summary.found as u64,
+            ),
+            (
+                "putzen_last_run_cleaned",
+                "Folders deleted in the last journaled cleanup.",
+                run.summary.cleaned as u64,
+            ),
+            (
+                "putzen_last_run_failed",
+                "Folders that failed to delete in the last journaled cleanup.",
+                run.summary.failed as u64,
+            ),
+            (
+                "putzen_last_run_freed_bytes",
+                "Bytes freed in the last journaled cleanup.",
+                run.summary.freed_bytes,
+            ),
+        ];
+        let labels = format!("dry_run=\"{}\"", run.dry_run);
+        for (name, help, value) in counters {
+            header(&mut out, name, help);
+            sample(&mut out, name, &labels, value);
+        }
+    }
+    out
+}
+
+/// Write `text` to `path` via a temporary file and a rename, so the
+/// collector never reads a half-written file.
+pub fn write_atomically(path: &Path, text: &str) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, text)?;
+    std::fs::rename(&tmp, path)
+}
+
+fn header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} gauge");
+}
+
+fn sample(out: &mut String, name: &str, labels: &str, value: u64) {
+    if labels.is_empty() {
+        let _ = writeln!(out, "{name} {value}");
+    } else {
+        let _ = writeln!(out, "{name}{{{labels}}} {value}");
+    }
+}
+
+/// Label values escape backslash, double quote and newline.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caches::scan::stat_dir;
+    use crate::FolderStats;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn entry(ecosystem: &'static str, size: usize, days_ago: u64, now: SystemTime) -> ListEntry {
+        ListEntry {
+            path: PathBuf::from(format!("/src/{ecosystem}{size}")),
+            ecosystem,
+            stats: FolderStats {
+                size,
+                newest_mtime: Some(now - Duration::from_secs(days_ago * 86_400)),
+            },
+        }
+    }
+
+    #[test]
+    fn renders_gauges_per_ecosystem_and_cache() {
+        let now = SystemTime::now();
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_dir = tmp.path().join("we\"ird");
+        std::fs::create_dir_all(&cache_dir).unwrap();
+        std::fs::write(cache_dir.join("blob"), vec![0u8; 300]).unwrap();
+        let artifacts = [
+            entry("rust", 100, 2, now),
+            entry("rust", 50, 10, now),
+            entry("javascript", 7, 1, now),
+        ];
+
+        let text = render(&artifacts, &[stat_dir(&cache_dir)], None, now);
+        assert!(text.contains("# TYPE putzen_artifact_reclaimable_bytes gauge\n"));
+        assert!(text.contains("putzen_artifact_reclaimable_bytes{ecosystem=\"rust\"} 150\n"));
+        assert!(text.contains("putzen_artifact_folders{ecosystem=\"javascript\"} 1\n"));
+        assert!(text.contains("putzen_oldest_artifact_age_seconds 864000\n"));
+        assert!(
+            text.contains("putzen_cache_reclaimable_bytes{label=\"we\\\"ird\""),
+            "got:\n{text}"
+        );
+        assert!(text.contains("} 300\n"));
+        assert!(!text.contains("putzen_last_run"));
+    }
+
+    #[test]
+    fn last_run_is_the_newest_summary_in_the_journal() {
+        let journal = concat!(
+            r#"{"timestamp":"2024-05-01T12:00:00Z","dry_run":false,"event":"summary","found":1,"cleaned":1,"failed":0,"freed_bytes":10,"aborted":false}"#,
+            "\n",
+            r#"{"timestamp":"2024-05-02T12:00:00Z","dry_run":false,"event":"summary","found":3,"cleaned":2,"failed":1,"freed_bytes":42,"aborted":true}"#,
+            "\n",
+            r#"{"timestamp":"2024-05-02T12:00:01Z","dry_run":false,"event":"disk_pressure","path":"/","free_bytes":1,"below_bytes":2}"#,
+            "\n",
+            r#"{"timestamp":"2024-05-02T12:00:02Z","dry_run":false,"event":"candi"#,
+        );
+        let run = LastRun::from_journal(journal).unwrap();
+        assert_eq!(run.timestamp, 1_714_651_200);
+        assert_eq!(run.summary.cleaned, 2);
+        assert_eq!(run.summary.freed_bytes, 42);
+
+        let text = render(&[], &[], Some(&run), SystemTime::now());
+        assert!(text.contains("putzen_last_run_freed_bytes{dry_run=\"false\"} 42\n"));
+        assert!(text.contains("putzen_last_run_timestamp_seconds{dry_run=\"false\"} 1714651200\n"));
+        assert!(LastRun::from_journal("").is_none());
+    }
+
+    #[test]
+    fn write_atomically_replaces_the_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("putzen.prom");
+        write_atomically(&path, "a 1\n").unwrap();
+        write_atomically(&path, "a 2\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a 2\n");
+        assert!(!tmp.path().join("putzen.prom.tmp").exists());
+    }
+}
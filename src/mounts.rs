@@ -0,0 +1,282 @@
+//! Keep the walk off network and FUSE filesystems. A dead NFS server or a
+//! slow sshfs mount would otherwise stall the walk for jwalk's whole
+//! `busy_timeout`.
+//!
+//! On Linux, mount points and their types come from `/proc/self/mountinfo`.
+//! On macOS and the BSDs, which have no such file, every directory is asked
+//! with `statfs` whether it is a mount point and of which type. Elsewhere
+//! nothing is skipped.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const MOUNTINFO: &str = "/proc/self/mountinfo";
+
+/// Filesystem types that are skipped unless included with `--include-fs`.
+/// Plain `fuse` and every `fuse.<name>` count as well, except `fuseblk`
+/// (local disks such as ntfs-3g).
+const REMOTE_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "9p",
+    "afs",
+    "ceph",
+    "glusterfs",
+    "lustre",
+    "davfs",
+    "sshfs",
+    // `f_fstypename` on macOS and the BSDs
+    "afpfs",
+    "webdav",
+    "macfuse",
+    "osxfuse",
+    "fusefs",
+];
+
+/// `true` for network filesystems and FUSE mounts.
+pub fn is_remote(fs_type: &str) -> bool {
+    REMOTE_TYPES.contains(&fs_type)
+        || fs_type == "fuse"
+        || (fs_type.starts_with("fuse.") && fs_type != "fuseblk")
+        || fs_type.starts_with("fusefs.")
+}
+
+/// Does `--include-fs <name>` cover `fs_type`? `nfs` covers `nfs4` and
+/// `sshfs` covers `fuse.sshfs` and FreeBSD's `fusefs.sshfs`.
+fn is_included(fs_type: &str, include: &[String]) -> bool {
+    include.iter().any(|name| {
+        let fs_type = fs_type
+            .strip_prefix("fuse.")
+            .or_else(|| fs_type.strip_prefix("fusefs."))
+            .unwrap_or(fs_type);
+        fs_type
+            .strip_prefix(name.as_str())
+            .is_some_and(|version| version.chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+/// A mount point the walk did not enter.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedMount {
+    pub path: PathBuf,
+    pub fs_type: String,
+}
+
+/// The remote mount points of this machine. Remembers which of them a walk
+/// ran into, for the summary.
+#[derive(Debug, Default)]
+pub struct MountFilter {
+    remote: HashMap<PathBuf, String>,
+    /// Without a mount table, ask `statfs` about every directory; types
+    /// named here are walked.
+    probe: Option<Vec<String>>,
+    encountered: Mutex<Vec<SkippedMount>>,
+}
+
+impl MountFilter {
+    /// Read this machine's mounts; types named in `include` are walked.
+    pub fn load(include: &[String]) -> Self {
+        match std::fs::read_to_string(MOUNTINFO) {
+            Ok(text) => Self::from_mountinfo(&text, include),
+            Err(_) if statfs::AVAILABLE => Self {
+                probe: Some(include.to_vec()),
+                ..Self::default()
+            },
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn from_mountinfo(text: &str, include: &[String]) -> Self {
+        let remote = text
+            .lines()
+            .filter_map(parse_line)
+            .filter(|(_, fs_type)| is_remote(fs_type) && !is_included(fs_type, include))
+            .collect();
+        Self {
+            remote,
+            probe: None,
+            encountered: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Should the walk leave `dir` out? Records it when so.
+    pub fn skips(&self, dir: &Path) -> bool {
+        let fs_type = match (self.remote.get(dir), &self.probe) {
+            (Some(fs_type), _) => fs_type.clone(),
+            (None, Some(include)) => match statfs::mount_at(dir) {
+                Some(fs_type) if is_remote(&fs_type) && !is_included(&fs_type, include) => fs_type,
+                _ => return false,
+            },
+            (None, None) => return false,
+        };
+        self.encountered.lock().unwrap().push(SkippedMount {
+            path: dir.to_path_buf(),
+            fs_type,
+        });
+        true
+    }
+
+    /// The mount points skipped so far, sorted by path.
+    pub fn skipped(&self) -> Vec<SkippedMount> {
+        let mut skipped = self.encountered.lock().unwrap().clone();
+        skipped.sort_by(|a, b| a.path.cmp(&b.path));
+        skipped.dedup();
+        skipped
+    }
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "openbsd"
+))]
+mod statfs {
+    use std::ffi::{CStr, CString};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    pub const AVAILABLE: bool = true;
+
+    /// The filesystem type of `dir` when it is a mount point itself.
+    pub fn mount_at(dir: &Path) -> Option<String> {
+        let c_dir = CString::new(dir.as_os_str().as_bytes()).ok()?;
+        let mut buf = std::mem::MaybeUninit::<libc::statfs>::uninit();
+        // SAFETY: `c_dir` is NUL-terminated and `buf` is a valid out pointer.
+        if unsafe { libc::statfs(c_dir.as_ptr(), buf.as_mut_ptr()) } != 0 {
+            return None;
+        }
+        // SAFETY: statfs succeeded, so it filled `buf`.
+        let buf = unsafe { buf.assume_init() };
+        // SAFETY: the kernel NUL-terminates both names.
+        let (on, fs_type) = unsafe {
+            (
+                CStr::from_ptr(buf.f_mntonname.as_ptr()),
+                CStr::from_ptr(buf.f_fstypename.as_ptr()),
+            )
+        };
+        (on.to_bytes() == c_dir.as_bytes()).then(|| fs_type.to_string_lossy().into_owned())
+    }
+}
+
+#[cfg(not(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "openbsd"
+)))]
+mod statfs {
+    use std::path::Path;
+
+    pub const AVAILABLE: bool = false;
+
+    pub fn mount_at(_dir: &Path) -> Option<String> {
+        None
+    }
+}
+
+/// Mount point and type of one mountinfo line:
+/// `36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw`.
+/// Optional fields end at the lone `-`.
+fn parse_line(line: &str) -> Option<(PathBuf, String)> {
+    let mut fields = line.split(' ');
+    let mount_point = fields.nth(4)?;
+    let fs_type = fields.skip_while(|f| *f != "-").nth(1)?;
+    Some((PathBuf::from(unescape(mount_point)), fs_type.to_string()))
+}
+
+/// Undo the octal escapes of mountinfo, e.g. `\040` for a space.
+fn unescape(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes.get(i + 1..i + 4).and_then(|digits| {
+            let digits = std::str::from_utf8(digits).ok()?;
+            u8::from_str_radix(digits, 8).ok()
+        });
+        match (bytes[i], octal) {
+            (b'\\', Some(byte)) => {
+                out.push(byte);
+                i += 4;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTINFO: &str = "\
+22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw
+40 22 0:45 / /home/shared rw,relatime shared:20 - nfs4 server:/export rw,vers=4.2
+41 22 0:46 / /mnt/my\\040box rw,nosuid - fuse.sshfs me@box: rw,user_id=1000
+42 22 8:17 / /mnt/windows rw - fuseblk /dev/sdb1 rw
+43 22 0:47 / /mnt/office rw - cifs //nas/office rw
+";
+
+    #[test]
+    fn skips_network_and_fuse_mounts() {
+        let filter = MountFilter::from_mountinfo(MOUNTINFO, &[]);
+        assert!(filter.skips(Path::new("/home/shared")));
+        assert!(filter.skips(Path::new("/mnt/my box")));
+        assert!(filter.skips(Path::new("/mnt/office")));
+        assert!(!filter.skips(Path::new("/mnt/windows")));
+        assert!(!filter.skips(Path::new("/")));
+        assert!(!filter.skips(Path::new("/home/shared/src")));
+
+        let skipped = filter.skipped();
+        assert_eq!(skipped.len(), 3);
+        assert_eq!(skipped[0].path, Path::new("/home/shared"));
+        assert_eq!(skipped[0].fs_type, "nfs4");
+    }
+
+    #[test]
+    fn include_fs_opts_back_in() {
+        let include = ["nfs".to_string(), "sshfs".to_string()];
+        let filter = MountFilter::from_mountinfo(MOUNTINFO, &include);
+        assert!(!filter.skips(Path::new("/home/shared")));
+        assert!(!filter.skips(Path::new("/mnt/my box")));
+        assert!(filter.skips(Path::new("/mnt/office")));
+    }
+
+    #[test]
+    fn knows_the_statfs_type_names() {
+        for remote in [
+            "nfs",
+            "smbfs",
+            "afpfs",
+            "webdav",
+            "macfuse",
+            "osxfuse",
+            "fusefs.sshfs",
+        ] {
+            assert!(is_remote(remote), "{remote}");
+        }
+        for local in ["apfs", "hfs", "ufs", "zfs", "msdos", "devfs"] {
+            assert!(!is_remote(local), "{local}");
+        }
+        assert!(is_included("fusefs.sshfs", &["sshfs".to_string()]));
+        assert!(is_included("smbfs", &["smbfs".to_string()]));
+    }
+
+    #[test]
+    fn parses_escaped_mount_points() {
+        assert_eq!(unescape("/a\\040b\\134c"), "/a b\\c");
+        assert_eq!(unescape("/trailing\\04"), "/trailing\\04");
+        assert_eq!(parse_line("garbage"), None);
+    }
+}
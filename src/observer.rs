@@ -1,3 +1,5 @@
+use crate::report::Event;
+
 pub trait RunObserver {
     /// Called after a folder is successfully cleaned.
     /// Returns an optional hint string (e.g. "🏆 new highscore!") to display inline.
@@ -6,6 +8,10 @@ pub trait RunObserver {
     /// Called after the entire run completes.
     /// Returns an optional string with medal ASCII art to display.
     fn on_run_complete(&mut self, total: u64) -> Option<String>;
+
+    /// Called for every step of a run, see [`crate::report`].
+    /// Observers that only care about the totals can ignore it.
+    fn on_event(&mut self, _event: &Event) {}
 }
 
 pub struct NoOpObserver;
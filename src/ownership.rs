@@ -0,0 +1,191 @@
+//! Only clean folders owned by the right user. On shared build servers a
+//! sweep must not run into other users' `target` folders, or worse, delete
+//! them as root.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// A candidate left alone because someone else owns it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForeignFolder {
+    pub path: PathBuf,
+    pub uid: u32,
+    /// The user name, when the uid has one.
+    pub owner: Option<String>,
+}
+
+/// Lets through the folders owned by one uid and remembers the others, for
+/// the summary.
+#[derive(Debug, Default)]
+pub struct OwnerFilter {
+    /// `None` lets everything through.
+    uid: Option<u32>,
+    skipped: Mutex<Vec<ForeignFolder>>,
+}
+
+impl OwnerFilter {
+    /// The effective user by default, anyone with `--any-owner`, or the
+    /// user (name or uid) given with `--owner`.
+    pub fn new(any_owner: bool, owner: Option<&str>) -> Result<Self, String> {
+        let uid = match (any_owner, owner) {
+            (true, Some(_)) => {
+                return Err("`--any-owner` and `--owner` are mutually exclusive".into())
+            }
+            (true, None) => None,
+            (false, Some(owner)) => Some(
+                owner
+                    .parse()
+                    .ok()
+                    .or_else(|| uid_of(owner))
+                    .ok_or_else(|| format!("unknown user `{owner}`"))?,
+            ),
+            (false, None) => effective_uid(),
+        };
+        Ok(Self::for_uid(uid))
+    }
+
+    pub fn for_uid(uid: Option<u32>) -> Self {
+        Self {
+            uid,
+            skipped: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Is `path` owned by the wanted user? Records it when not.
+    pub fn allows(&self, path: &Path) -> bool {
+        let Some(wanted) = self.uid else {
+            return true;
+        };
+        let Some(uid) = owner_uid(path) else {
+            return true;
+        };
+        if uid == wanted {
+            return true;
+        }
+        self.skipped.lock().unwrap().push(ForeignFolder {
+            path: path.to_path_buf(),
+            uid,
+            owner: name_of(uid),
+        });
+        false
+    }
+
+    /// The folders skipped so far, sorted by path.
+    pub fn skipped(&self) -> Vec<ForeignFolder> {
+        let mut skipped = self.skipped.lock().unwrap().clone();
+        skipped.sort_by(|a, b| a.path.cmp(&b.path));
+        skipped
+    }
+}
+
+#[cfg(unix)]
+fn effective_uid() -> Option<u32> {
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    Some(unsafe { libc::geteuid() })
+}
+
+#[cfg(not(unix))]
+fn effective_uid() -> Option<u32> {
+    None
+}
+
+#[cfg(unix)]
+fn owner_uid(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::symlink_metadata(path).ok().map(|m| m.uid())
+}
+
+#[cfg(not(unix))]
+fn owner_uid(_path: &Path) -> Option<u32> {
+    None
+}
+
+/// Look up a user's uid in the passwd database.
+#[cfg(unix)]
+fn uid_of(name: &str) -> Option<u32> {
+    let name = std::ffi::CString::new(name).ok()?;
+    let mut buf = vec![0 as libc::c_char; 16 * 1024];
+    // SAFETY: `passwd` is a plain C struct, filled in by getpwnam_r with
+    // pointers into `buf`, which outlives every read below.
+    unsafe {
+        let mut pwd: libc::passwd = std::mem::zeroed();
+        let mut found = std::ptr::null_mut();
+        let rc = libc::getpwnam_r(
+            name.as_ptr(),
+            &mut pwd,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut found,
+        );
+        (rc == 0 && !found.is_null()).then_some(pwd.pw_uid)
+    }
+}
+
+#[cfg(not(unix))]
+fn uid_of(_name: &str) -> Option<u32> {
+    None
+}
+
+/// Look up the user name of `uid` in the passwd database.
+#[cfg(unix)]
+fn name_of(uid: u32) -> Option<String> {
+    let mut buf = vec![0 as libc::c_char; 16 * 1024];
+    // SAFETY: as in `uid_of`; `pw_name` points into `buf` and is copied
+    // out before `buf` is dropped.
+    unsafe {
+        let mut pwd: libc::passwd = std::mem::zeroed();
+        let mut found = std::ptr::null_mut();
+        let rc = libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut found);
+        if rc != 0 || found.is_null() {
+            return None;
+        }
+        Some(
+            std::ffi::CStr::from_ptr(pwd.pw_name)
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+}
+
+#[cfg(not(unix))]
+fn name_of(_uid: u32) -> Option<String> {
+    None
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_and_records_foreign_folders() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mine = owner_uid(tmp.path()).unwrap();
+
+        let filter = OwnerFilter::for_uid(Some(mine));
+        assert!(filter.allows(tmp.path()));
+        assert!(filter.skipped().is_empty());
+
+        let filter = OwnerFilter::for_uid(Some(mine + 1));
+        assert!(!filter.allows(tmp.path()));
+        let skipped = filter.skipped();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].uid, mine);
+
+        assert!(OwnerFilter::for_uid(None).allows(tmp.path()));
+    }
+
+    #[test]
+    fn owner_flags() {
+        assert_eq!(OwnerFilter::new(false, None).unwrap().uid, effective_uid());
+        assert_eq!(OwnerFilter::new(true, None).unwrap().uid, None);
+        assert_eq!(
+            OwnerFilter::new(false, Some("1234")).unwrap().uid,
+            Some(1234)
+        );
+        assert_eq!(OwnerFilter::new(false, Some("root")).unwrap().uid, Some(0));
+        assert!(OwnerFilter::new(false, Some("no-such-user-here")).is_err());
+        assert!(OwnerFilter::new(true, Some("root")).is_err());
+        assert_eq!(name_of(0).as_deref(), Some("root"));
+    }
+}
@@ -0,0 +1,307 @@
+//! Dry-run plan files: `putzen -d --plan-out plan.json` records every folder
+//! the dry-run would have deleted, `putzen apply plan.json` deletes exactly
+//! those later — after checking that none of them changed in between.
+//!
+//! A folder's fingerprint is its path, size, newest mtime and the rule that
+//! matched it. If any of them drifted, the folder is no longer the one that
+//! was reviewed.
+
+use crate::report::{rfc3339, Event, EventKind, RuleInfo};
+use crate::{FileToFolderMatch, Folder, IsFolderToRemove, RunObserver};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever the file layout changes incompatibly.
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanFile {
+    pub version: u32,
+    /// RFC 3339 time the plan was written.
+    pub created: String,
+    /// The folder the dry-run started at.
+    pub root: PathBuf,
+    pub candidates: Vec<PlannedFolder>,
+}
+
+impl PlanFile {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let plan: Self = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        if plan.version != VERSION {
+            return Err(format!(
+                "plan file version {} is not supported, expected {VERSION}",
+                plan.version
+            ));
+        }
+        Ok(plan)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedFolder {
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    /// RFC 3339 mtime of the newest file inside, `None` when empty.
+    pub newest_mtime: Option<String>,
+    pub rule: PlannedRule,
+}
+
+/// [`RuleInfo`] as read back from a plan file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedRule {
+    pub ecosystem: String,
+    pub file_to_check: String,
+    pub folder_to_remove: String,
+}
+
+impl From<&RuleInfo> for PlannedRule {
+    fn from(rule: &RuleInfo) -> Self {
+        Self {
+            ecosystem: rule.ecosystem.to_string(),
+            file_to_check: rule.file_to_check.to_string(),
+            folder_to_remove: rule.folder_to_remove.to_string(),
+        }
+    }
+}
+
+impl PlannedRule {
+    pub fn matches(&self, rule: &FileToFolderMatch) -> bool {
+        self.file_to_check == rule.file_to_check()
+            && self.folder_to_remove == rule.folder_to_remove()
+    }
+}
+
+/// Why a planned folder is not the one that was reviewed anymore.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Drift {
+    Missing,
+    /// The path now resolves somewhere else, e.g. through a swapped symlink.
+    Moved(PathBuf),
+    /// The rule is not known to this putzen.
+    UnknownRule,
+    /// E.g. the `Cargo.toml` next to `target` is gone.
+    RuleNoLongerMatches,
+    SizeChanged {
+        planned: u64,
+        now: u64,
+    },
+    Modified {
+        planned: Option<String>,
+        now: Option<String>,
+    },
+}
+
+impl Display for Drift {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let or_empty = |t: &Option<String>| t.clone().unwrap_or_else(|| "empty".into());
+        match self {
+            Drift::Missing => write!(f, "is gone"),
+            Drift::Moved(to) => write!(f, "now resolves to {}", to.display()),
+            Drift::UnknownRule => write!(f, "was matched by a rule this putzen does not know"),
+            Drift::RuleNoLongerMatches => write!(f, "is no longer matched by its rule"),
+            Drift::SizeChanged { planned, now } => {
+                write!(f, "size changed from {planned} to {now} bytes")
+            }
+            Drift::Modified { planned, now } => write!(
+                f,
+                "newest file changed from {} to {}",
+                or_empty(planned),
+                or_empty(now)
+            ),
+        }
+    }
+}
+
+impl PlannedFolder {
+    /// Compare the folder on disk with its fingerprint. `rule` is the
+    /// recorded rule resolved against the known rules.
+    pub fn verify(&self, rule: Option<&FileToFolderMatch>) -> Result<Folder, Drift> {
+        let folder = Folder::try_from(self.path.clone()).map_err(|_| Drift::Missing)?;
+        if folder.as_ref() != self.path {
+            return Err(Drift::Moved(folder.as_ref().to_path_buf()));
+        }
+        let rule = rule.ok_or(Drift::UnknownRule)?;
+        if !rule.is_folder_to_remove(&folder) {
+            return Err(Drift::RuleNoLongerMatches);
+        }
+        let stats = folder.stats();
+        let newest_mtime = stats.newest_mtime.and_then(rfc3339);
+        if newest_mtime != self.newest_mtime {
+            return Err(Drift::Modified {
+                planned: self.newest_mtime.clone(),
+                now: newest_mtime,
+            });
+        }
+        if stats.size as u64 != self.size_bytes {
+            return Err(Drift::SizeChanged {
+                planned: self.size_bytes,
+                now: stats.size as u64,
+            });
+        }
+        Ok(folder)
+    }
+}
+
+/// Records every folder a dry-run would delete and writes the plan to `out`
+/// once the run is over. Forwards everything to the wrapped observer.
+pub struct PlanRecorder<W: Write> {
+    out: W,
+    plan: PlanFile,
+    /// The last `candidate_found`, waiting for its outcome.
+    pending: Option<PlannedFolder>,
+    inner: Box<dyn RunObserver>,
+}
+
+impl<W: Write> PlanRecorder<W> {
+    pub fn new(root: &Path, out: W, inner: Box<dyn RunObserver>) -> Self {
+        Self {
+            out,
+            plan: PlanFile {
+                version: VERSION,
+                created: String::new(),
+                root: root.to_path_buf(),
+                candidates: Vec::new(),
+            },
+            pending: None,
+            inner,
+        }
+    }
+
+    pub fn into_writer(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> RunObserver for PlanRecorder<W> {
+    fn on_folder_cleaned(&mut self, size: u64) -> Option<String> {
+        self.inner.on_folder_cleaned(size)
+    }
+
+    fn on_run_complete(&mut self, total: u64) -> Option<String> {
+        self.inner.on_run_complete(total)
+    }
+
+    fn on_event(&mut self, event: &Event) {
+        match &event.kind {
+            EventKind::CandidateFound {
+                path,
+                rule,
+                size_bytes,
+                newest_mtime,
+            } => {
+                self.pending = Some(PlannedFolder {
+                    path: PathBuf::from(path),
+                    size_bytes: *size_bytes,
+                    newest_mtime: newest_mtime.clone(),
+                    rule: rule.into(),
+                })
+            }
+            // in a dry-run, `cleaned` means "would have been deleted"
+            EventKind::Cleaned { path, .. } => {
+                if let Some(planned) = self.pending.take_if(|p| p.path == Path::new(path)) {
+                    self.plan.candidates.push(planned);
+                }
+            }
+            EventKind::Summary(_) => {
+                self.plan.created = event.timestamp.clone();
+                // a failing write shows up as a missing plan file; the run
+                // itself must not abort over it
+                if serde_json::to_writer_pretty(&mut self.out, &self.plan).is_ok() {
+                    let _ = writeln!(self.out);
+                }
+            }
+            _ => {}
+        }
+        self.inner.on_event(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NoOpObserver;
+
+    static RULE: FileToFolderMatch = FileToFolderMatch::new("Cargo.toml", "target");
+
+    fn project(root: &Path) -> Folder {
+        std::fs::write(root.join("Cargo.toml"), "").unwrap();
+        let target = root.join("target");
+        std::fs::create_dir_all(&target).unwrap();
+        std::fs::write(target.join("artefact"), vec![0u8; 100]).unwrap();
+        Folder::try_from(target).unwrap()
+    }
+
+    fn record(folder: &Folder) -> PlanFile {
+        let mut recorder = PlanRecorder::new(Path::new("/src"), Vec::new(), Box::new(NoOpObserver));
+        let stats = folder.stats();
+        let path = folder.to_string();
+        for kind in [
+            EventKind::CandidateFound {
+                path: path.clone(),
+                rule: (&RULE).into(),
+                size_bytes: stats.size as u64,
+                newest_mtime: stats.newest_mtime.and_then(rfc3339),
+            },
+            EventKind::Cleaned {
+                path,
+                rule: (&RULE).into(),
+                size_bytes: stats.size as u64,
+            },
+            EventKind::CandidateFound {
+                path: "/src/kept/target".into(),
+                rule: (&RULE).into(),
+                size_bytes: 1,
+                newest_mtime: None,
+            },
+            EventKind::Summary(Default::default()),
+        ] {
+            recorder.on_event(&Event::new(true, kind));
+        }
+        PlanFile::parse(std::str::from_utf8(&recorder.into_writer()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn records_only_what_would_be_deleted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let folder = project(tmp.path());
+        let plan = record(&folder);
+        assert_eq!(plan.version, VERSION);
+        assert_eq!(plan.candidates.len(), 1);
+        assert_eq!(plan.candidates[0].path, folder.as_ref());
+        assert_eq!(plan.candidates[0].size_bytes, 100);
+        assert!(plan.candidates[0].rule.matches(&RULE));
+        assert!(plan.candidates[0].verify(Some(&RULE)).is_ok());
+    }
+
+    #[test]
+    fn verify_reports_drift() {
+        let tmp = tempfile::tempdir().unwrap();
+        let folder = project(tmp.path());
+        let planned = record(&folder).candidates.remove(0);
+
+        std::fs::write(folder.as_ref().join("artefact"), vec![0u8; 200]).unwrap();
+        assert!(matches!(
+            planned.verify(Some(&RULE)),
+            Err(Drift::SizeChanged { .. } | Drift::Modified { .. })
+        ));
+
+        std::fs::remove_file(tmp.path().join("Cargo.toml")).unwrap();
+        assert_eq!(
+            planned.verify(Some(&RULE)).unwrap_err(),
+            Drift::RuleNoLongerMatches
+        );
+        assert_eq!(planned.verify(None).unwrap_err(), Drift::UnknownRule);
+
+        std::fs::remove_dir_all(folder.as_ref()).unwrap();
+        assert_eq!(planned.verify(Some(&RULE)).unwrap_err(), Drift::Missing);
+    }
+
+    #[test]
+    fn parse_rejects_other_versions() {
+        let text = r#"{"version":2,"created":"","root":"/","candidates":[]}"#;
+        assert!(PlanFile::parse(text).is_err());
+    }
+}
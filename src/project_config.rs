@@ -0,0 +1,215 @@
+//! Per-project overrides from a `.putzen.toml` in a project root.
+//!
+//! ```toml
+//! keep = true                    # never clean anything below this folder
+//! extra_targets = ["generated"]  # more folders here that are safe to delete
+//! older_than = "30d"             # only clean artifacts untouched this long
+//! ```
+//!
+//! The walker picks the file up when it enters the folder, and the settings
+//! apply to that subtree only. A `.putzen.toml` further down replaces it for
+//! its own subtree.
+
+use crate::caches::parse_duration;
+use crate::{FileToFolderMatch, Folder, IsFolderToRemove};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+pub const FILE_NAME: &str = ".putzen.toml";
+
+/// Version control folders and this file itself, which `extra_targets`
+/// must never name: they hold the only copy of history and settings.
+const PROTECTED: &[&str] = &[
+    ".git", ".hg", ".svn", ".jj", ".bzr", "_darcs", ".pijul", ".fossil", "CVS", FILE_NAME,
+];
+
+/// Ecosystem shown for folders matched through `extra_targets`.
+pub const EXTRA_ECOSYSTEM: &str = "custom";
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub keep: bool,
+    /// Folder names next to the `.putzen.toml`.
+    pub extra_targets: Vec<String>,
+    /// A duration like `30d`, see [`parse_duration`].
+    pub older_than: Option<String>,
+}
+
+impl ProjectConfig {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let config: Self = toml::from_str(text).map_err(|e| e.to_string())?;
+        for name in &config.extra_targets {
+            if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+                return Err(format!(
+                    "extra target `{name}` must be a plain folder name in the project root"
+                ));
+            }
+            if PROTECTED.iter().any(|p| p.eq_ignore_ascii_case(name)) {
+                return Err(format!("extra target `{name}` is never safe to delete"));
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// The `.putzen.toml` in effect for a subtree, as handed down the walk.
+#[derive(Debug)]
+pub struct ProjectScope {
+    /// The folder holding the `.putzen.toml`.
+    pub root: PathBuf,
+    pub keep: bool,
+    pub extra_rules: Vec<&'static FileToFolderMatch>,
+    pub older_than: Option<Duration>,
+}
+
+impl ProjectScope {
+    /// Read `dir/.putzen.toml`; `Ok(None)` when there is none.
+    pub fn load(dir: &Path) -> Result<Option<Self>, String> {
+        let text = match std::fs::read_to_string(dir.join(FILE_NAME)) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.to_string()),
+        };
+        // `Folder` paths are canonical, so compare against a canonical root
+        let root = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        Self::from_config(&root, ProjectConfig::parse(&text)?).map(Some)
+    }
+
+    pub fn from_config(root: &Path, config: ProjectConfig) -> Result<Self, String> {
+        Ok(Self {
+            root: root.to_path_buf(),
+            keep: config.keep,
+            extra_rules: config.extra_targets.iter().map(|n| extra_rule(n)).collect(),
+            older_than: config
+                .older_than
+                .as_deref()
+                .map(parse_duration)
+                .transpose()?,
+        })
+    }
+
+    /// The rule matching `folder` when it is one of the extra targets of
+    /// this project.
+    pub fn extra_rule_for(&self, folder: &Folder) -> Option<&'static FileToFolderMatch> {
+        if folder.as_ref().parent() != Some(self.root.as_path()) {
+            return None;
+        }
+        self.extra_rules
+            .iter()
+            .copied()
+            .find(|rule| rule.is_folder_to_remove(folder))
+    }
+}
+
+/// The rule for an extra target `name`: "a `.putzen.toml` next to it".
+/// Rules are `'static` like `FOLDER_TO_CLEANUP`, so each name is interned
+/// once per run.
+pub fn extra_rule(name: &str) -> &'static FileToFolderMatch {
+    static RULES: OnceLock<Mutex<HashMap<String, &'static FileToFolderMatch>>> = OnceLock::new();
+    let mut rules = RULES.get_or_init(Default::default).lock().unwrap();
+    rules.entry(name.to_string()).or_insert_with(|| {
+        let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+        Box::leak(Box::new(
+            FileToFolderMatch::new(FILE_NAME, name).with_ecosystem(EXTRA_ECOSYSTEM),
+        ))
+    })
+}
+
+/// `true` when nothing inside `folder` was modified within `older_than`.
+/// Empty folders are always old enough.
+pub fn is_old_enough(folder: &Folder, older_than: Duration, now: SystemTime) -> bool {
+    // This runs inside the walker's rayon pool, where a nested jwalk (as in
+    // `Folder::stats`) would wait on its own pool. A plain serial walk also
+    // stops at the first file that is too young.
+    let mut dirs = vec![folder.as_ref().to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                dirs.push(entry.path());
+            } else if let Ok(mtime) = meta.modified() {
+                if now.duration_since(mtime).unwrap_or_default() < older_than {
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn parses_all_keys() {
+        let config = ProjectConfig::parse(
+            "keep = true\nextra_targets = [\"generated\"]\nolder_than = \"30d\"\n",
+        )
+        .unwrap();
+        assert!(config.keep);
+        assert_eq!(config.extra_targets, ["generated"]);
+        assert_eq!(config.older_than.as_deref(), Some("30d"));
+    }
+
+    #[test]
+    fn rejects_nested_extra_targets_and_unknown_keys() {
+        assert!(ProjectConfig::parse("extra_targets = [\"src/gen\"]").is_err());
+        assert!(ProjectConfig::parse("extra_targets = [\"..\"]").is_err());
+        assert!(ProjectConfig::parse("kepp = true").is_err());
+    }
+
+    #[test]
+    fn rejects_vcs_folders_and_itself() {
+        for name in [".git", ".hg", ".svn", ".jj", ".GIT", ".putzen.toml"] {
+            let text = format!("extra_targets = [\"{name}\"]");
+            assert!(ProjectConfig::parse(&text).is_err(), "{name}");
+        }
+        assert!(ProjectConfig::parse("extra_targets = [\".gitlab\"]").is_ok());
+    }
+
+    #[test]
+    fn load_without_file_is_none() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(ProjectScope::load(tmp.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn extra_targets_match_only_in_the_project_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join(FILE_NAME), "extra_targets = [\"generated\"]").unwrap();
+        std::fs::create_dir_all(root.join("generated")).unwrap();
+        std::fs::create_dir_all(root.join("sub").join("generated")).unwrap();
+        let scope = ProjectScope::load(&root).unwrap().unwrap();
+
+        let top = Folder::try_from(root.join("generated")).unwrap();
+        let rule = scope.extra_rule_for(&top).unwrap();
+        assert_eq!(rule.ecosystem(), EXTRA_ECOSYSTEM);
+        assert!(std::ptr::eq(rule, extra_rule("generated")));
+
+        let nested = Folder::try_from(root.join("sub").join("generated")).unwrap();
+        assert!(scope.extra_rule_for(&nested).is_none());
+    }
+
+    #[test]
+    fn age_threshold_checks_the_newest_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("fresh"), "x").unwrap();
+        let folder = Folder::try_from(tmp.path().to_path_buf()).unwrap();
+        let now = SystemTime::now();
+        let day = Duration::from_secs(86_400);
+        assert!(!is_old_enough(&folder, day, now));
+        assert!(is_old_enough(&folder, day, now + 2 * day));
+    }
+}
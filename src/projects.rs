@@ -0,0 +1,77 @@
+//! `putzen --tui` — full-screen cleanup of project build artifacts.
+//!
+//! The artifact folders found by the regular walk are fed into the
+//! `caches::tui` State/update/view loop, so marks, sorting, filtering,
+//! drill-in, dry-run and the delete modal behave exactly like in
+//! `putzen caches`.
+
+use crate::caches::{self, model, tui};
+use crate::{FileToFolderMatch, Folder};
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub struct ProjectsArgs {
+    /// Artifact folders found by the walk, with the rule that matched them.
+    pub folders: Vec<(Folder, &'static FileToFolderMatch)>,
+    pub dry_run: bool,
+    pub yes: bool,
+}
+
+pub fn run(args: ProjectsArgs) -> io::Result<()> {
+    let folders: Vec<(String, PathBuf)> = args
+        .folders
+        .iter()
+        .map(|(folder, rule)| (row_label(folder, rule), folder.as_ref().to_path_buf()))
+        .collect();
+
+    // Sizing every artifact folder is the slow part, so it runs on a worker
+    // (Effect::LoadFolders) behind the same spinner `putzen caches` shows.
+    let state = caches::initial_state(
+        "putzen — project artifacts",
+        model::FloorPolicy {
+            floor: Duration::from_secs(7 * 86_400),
+        },
+        args.dry_run,
+        args.yes,
+    );
+    caches::run_tui(state, vec![tui::Effect::LoadFolders { folders }])
+}
+
+/// Row label for an artifact folder: the project (parent directory) name
+/// followed by the ecosystem of the matching rule, e.g. `putzen-rs · rust`.
+pub fn row_label(folder: &Folder, rule: &FileToFolderMatch) -> String {
+    let project = folder
+        .as_ref()
+        .parent()
+        .and_then(|p| p.file_name())
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| folder.to_string());
+    format!("{project} · {}", rule.ecosystem())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn row_label_names_project_and_ecosystem() {
+        let tmp = tempfile::tempdir().unwrap();
+        let target = tmp.path().join("foo").join("target");
+        std::fs::create_dir_all(&target).unwrap();
+        let folder = Folder::try_from(target).unwrap();
+        let rule = FileToFolderMatch::new("Cargo.toml", "target").with_ecosystem("rust");
+        assert_eq!(row_label(&folder, &rule), "foo · rust");
+    }
+
+    #[test]
+    fn row_label_falls_back_to_folder_name_as_ecosystem() {
+        let tmp = tempfile::tempdir().unwrap();
+        let build = tmp.path().join("bar").join("build");
+        std::fs::create_dir_all(&build).unwrap();
+        let folder = Folder::try_from(build).unwrap();
+        let rule = FileToFolderMatch::new("CMakeLists.txt", "build");
+        assert_eq!(row_label(&folder, &rule), "bar · build");
+    }
+}
@@ -0,0 +1,279 @@
+//! Reclaim-to-target: `--until-free 50G` and `--free-at-least 20G`.
+//!
+//! Candidates are cleaned in order of their size × age score (the same idea
+//! as `Cache::score`) until the goal is met, so the least valuable bytes go
+//! first and the run stops as soon as there is enough room.
+
+use crate::{FolderStats, HumanReadable};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+/// Reports the space available to unprivileged users on the filesystem
+/// holding `path`. A trait so tests (and `putzen watch`) can fake it.
+pub trait FreeSpace {
+    fn available_bytes(&self, path: &Path) -> io::Result<u64>;
+}
+
+impl<T: FreeSpace + ?Sized> FreeSpace for Rc<T> {
+    fn available_bytes(&self, path: &Path) -> io::Result<u64> {
+        (**self).available_bytes(path)
+    }
+}
+
+/// The real filesystem, asked via `statvfs(3)`.
+pub struct Statvfs;
+
+impl FreeSpace for Statvfs {
+    #[cfg(unix)]
+    #[allow(clippy::unnecessary_cast)] // the field types differ between platforms
+    fn available_bytes(&self, path: &Path) -> io::Result<u64> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        // SAFETY: `c_path` is NUL-terminated and `stat` is a plain C struct
+        // that `statvfs` fills in; it is only read after a successful call.
+        let stat = unsafe {
+            let mut stat: libc::statvfs = std::mem::zeroed();
+            if libc::statvfs(c_path.as_ptr(), &mut stat) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            stat
+        };
+        Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+    }
+
+    #[cfg(not(unix))]
+    fn available_bytes(&self, _path: &Path) -> io::Result<u64> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "free disk space can only be read on unix",
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Goal {
+    /// Stop once the filesystem has this many bytes free.
+    UntilFree(u64),
+    /// Stop once this many bytes were freed.
+    FreeAtLeast(u64),
+}
+
+/// A goal bound to the filesystem it is measured on.
+pub struct Reclaim {
+    goal: Goal,
+    path: PathBuf,
+    probe: Box<dyn FreeSpace>,
+    free_before: u64,
+}
+
+impl Reclaim {
+    /// Remember the free space on `path` before anything is deleted.
+    pub fn start(goal: Goal, path: &Path, probe: Box<dyn FreeSpace>) -> io::Result<Self> {
+        let free_before = probe.available_bytes(path)?;
+        Ok(Self {
+            goal,
+            path: path.to_path_buf(),
+            probe,
+            free_before,
+        })
+    }
+
+    /// Free space right now. In a dry-run nothing was deleted, so it is
+    /// estimated from what would have been freed.
+    pub fn free_now(&self, freed: u64, dry_run: bool) -> io::Result<u64> {
+        if dry_run {
+            Ok(self.free_before + freed)
+        } else {
+            self.probe.available_bytes(&self.path)
+        }
+    }
+
+    /// Is the goal met after `freed` bytes were (or would have been) deleted?
+    pub fn is_met(&self, freed: u64, dry_run: bool) -> io::Result<bool> {
+        Ok(match self.goal {
+            Goal::UntilFree(target) => self.free_now(freed, dry_run)? >= target,
+            Goal::FreeAtLeast(target) => freed >= target,
+        })
+    }
+
+    /// One line saying whether the target was reached.
+    pub fn report(&self, freed: u64, dry_run: bool) -> io::Result<String> {
+        let reached = self.is_met(freed, dry_run)?;
+        let free_now = self.free_now(freed, dry_run)? as usize;
+        let (verb, free_label) = if dry_run {
+            ("would be", "would be free")
+        } else {
+            ("was", "free")
+        };
+        let outcome = if reached { "reached" } else { "NOT reached" };
+        Ok(match self.goal {
+            Goal::UntilFree(target) => format!(
+                "Target {outcome}: {} {free_label}, goal was {}",
+                free_now.as_human_readable(),
+                (target as usize).as_human_readable()
+            ),
+            Goal::FreeAtLeast(target) => format!(
+                "Target {outcome}: {} {verb} freed, goal was {}",
+                (freed as usize).as_human_readable(),
+                (target as usize).as_human_readable()
+            ),
+        })
+    }
+}
+
+/// Size × age score: (size MB) × (age days), like `Cache::score`. Empty
+/// folders score 0.
+pub fn score(stats: &FolderStats, now: SystemTime) -> f64 {
+    let Some(newest) = stats.newest_mtime else {
+        return 0.0;
+    };
+    let days = now.duration_since(newest).unwrap_or_default().as_secs_f64() / 86_400.0;
+    stats.size as f64 / 1_048_576.0 * days
+}
+
+/// Which candidates go first when cleaning towards a goal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rank {
+    /// Size × age, see [`score`].
+    #[default]
+    Score,
+    /// Oldest newest-file first.
+    Age,
+    /// Largest first.
+    Size,
+}
+
+impl Rank {
+    /// Sort key, highest goes first.
+    pub fn key(self, stats: &FolderStats, now: SystemTime) -> f64 {
+        match self {
+            Rank::Score => score(stats, now),
+            Rank::Age => stats.newest_mtime.map_or(0.0, |t| {
+                now.duration_since(t).unwrap_or_default().as_secs_f64()
+            }),
+            Rank::Size => stats.size as f64,
+        }
+    }
+}
+
+impl FromStr for Rank {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "score" => Ok(Rank::Score),
+            "age" => Ok(Rank::Age),
+            "size" => Ok(Rank::Size),
+            other => Err(format!(
+                "unknown rank `{other}`, expected `score`, `age` or `size`"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    const GIB: u64 = 1 << 30;
+
+    /// Free space that grows whenever the test says something was deleted.
+    struct FakeDisk(Rc<Cell<u64>>);
+
+    impl FreeSpace for FakeDisk {
+        fn available_bytes(&self, _path: &Path) -> io::Result<u64> {
+            Ok(self.0.get())
+        }
+    }
+
+    #[test]
+    fn until_free_reads_the_disk_after_real_deletions() {
+        let disk = Rc::new(Cell::new(10 * GIB));
+        let reclaim = Reclaim::start(
+            Goal::UntilFree(50 * GIB),
+            Path::new("/"),
+            Box::new(FakeDisk(disk.clone())),
+        )
+        .unwrap();
+        assert!(!reclaim.is_met(0, false).unwrap());
+
+        disk.set(55 * GIB);
+        assert!(reclaim.is_met(0, false).unwrap());
+        let report = reclaim.report(0, false).unwrap();
+        assert_eq!(report, "Target reached: 55.0GiB free, goal was 50.0GiB");
+    }
+
+    #[test]
+    fn until_free_estimates_in_a_dry_run() {
+        let disk = Rc::new(Cell::new(10 * GIB));
+        let reclaim = Reclaim::start(
+            Goal::UntilFree(50 * GIB),
+            Path::new("/"),
+            Box::new(FakeDisk(disk)),
+        )
+        .unwrap();
+        assert!(!reclaim.is_met(39 * GIB, true).unwrap());
+        assert!(reclaim.is_met(40 * GIB, true).unwrap());
+        let report = reclaim.report(20 * GIB, true).unwrap();
+        assert!(report.starts_with("Target NOT reached: 30.0GiB would be free"));
+    }
+
+    #[test]
+    fn free_at_least_counts_freed_bytes() {
+        let reclaim = Reclaim::start(
+            Goal::FreeAtLeast(20 * GIB),
+            Path::new("/"),
+            Box::new(FakeDisk(Rc::new(Cell::new(0)))),
+        )
+        .unwrap();
+        assert!(!reclaim.is_met(19 * GIB, false).unwrap());
+        assert!(reclaim.is_met(20 * GIB, false).unwrap());
+    }
+
+    #[test]
+    fn score_weighs_size_by_age() {
+        let now = SystemTime::now();
+        let stats = |mb: usize, days: u64| FolderStats {
+            size: mb * 1_048_576,
+            newest_mtime: Some(now - Duration::from_secs(days * 86_400)),
+        };
+        assert_eq!(score(&stats(100, 10), now), 1000.0);
+        assert!(score(&stats(10, 365), now) > score(&stats(1000, 1), now));
+        assert_eq!(score(&FolderStats::default(), now), 0.0);
+    }
+
+    #[test]
+    fn rank_keys_order_by_age_or_size() {
+        let now = SystemTime::now();
+        let old_small = FolderStats {
+            size: 10,
+            newest_mtime: Some(now - Duration::from_secs(86_400)),
+        };
+        let new_big = FolderStats {
+            size: 1000,
+            newest_mtime: Some(now),
+        };
+        assert!(Rank::Age.key(&old_small, now) > Rank::Age.key(&new_big, now));
+        assert!(Rank::Size.key(&new_big, now) > Rank::Size.key(&old_small, now));
+        assert_eq!("age".parse::<Rank>(), Ok(Rank::Age));
+        assert!("newest".parse::<Rank>().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn statvfs_reads_some_free_space() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(Statvfs.available_bytes(tmp.path()).unwrap() > 0);
+        assert!(Statvfs
+            .available_bytes(&tmp.path().join("missing"))
+            .is_err());
+    }
+}
@@ -0,0 +1,351 @@
+//! Machine-readable run reports for `--format json` and `--format ndjson`.
+//!
+//! `Folder::accept` emits an [`Event`] for every step of a run through
+//! [`RunObserver::on_event`]. The [`Reporter`] observer either streams them
+//! as one JSON object per line (`ndjson`) or folds them into a single JSON
+//! document printed at the end (`json`). In both modes everything meant for
+//! humans goes to stderr, so stdout stays parseable.
+
+use crate::mounts::SkippedMount;
+use crate::ownership::ForeignFolder;
+use crate::{FileToFolderMatch, RunObserver};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+/// How the main command reports on stdout.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable lines, the classic putzen output.
+    #[default]
+    Text,
+    /// One JSON document once the run is over.
+    Json,
+    /// One JSON event per line, as the run happens.
+    Ndjson,
+}
+
+impl OutputFormat {
+    /// `true` for the JSON modes, where stdout is reserved for the report.
+    pub fn is_machine_readable(self) -> bool {
+        self != OutputFormat::Text
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            other => Err(format!(
+                "unknown format `{other}`, expected `text`, `json` or `ndjson`"
+            )),
+        }
+    }
+}
+
+/// The rule that matched a folder, as it shows up in reports.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RuleInfo {
+    pub ecosystem: &'static str,
+    pub file_to_check: &'static str,
+    pub folder_to_remove: &'static str,
+}
+
+impl From<&FileToFolderMatch> for RuleInfo {
+    fn from(rule: &FileToFolderMatch) -> Self {
+        Self {
+            ecosystem: rule.ecosystem(),
+            file_to_check: rule.file_to_check(),
+            folder_to_remove: rule.folder_to_remove(),
+        }
+    }
+}
+
+/// Totals of a run, emitted as the last event. `cleaned` and `freed_bytes`
+/// only count real deletions, so they stay 0 in a dry-run.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunSummary {
+    /// Folders that matched a rule and were offered for deletion.
+    pub found: usize,
+    pub cleaned: usize,
+    pub failed: usize,
+    pub freed_bytes: u64,
+    /// The user quit, or a failed deletion stopped the run early.
+    pub aborted: bool,
+    /// Whether `--until-free` / `--free-at-least` was met; absent without one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_reached: Option<bool>,
+    /// Network and FUSE mount points the walk did not enter.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_mounts: Vec<SkippedMount>,
+    /// Candidates left alone because another user owns them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_foreign: Vec<ForeignFolder>,
+}
+
+/// One step of a run. Serializes flat, with the step name in `event`.
+#[derive(Clone, Debug, Serialize)]
+pub struct Event {
+    /// RFC 3339 time the event was emitted.
+    pub timestamp: String,
+    pub dry_run: bool,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum EventKind {
+    CandidateFound {
+        path: String,
+        rule: RuleInfo,
+        size_bytes: u64,
+        /// RFC 3339 mtime of the newest file inside, `None` when empty.
+        newest_mtime: Option<String>,
+    },
+    Decision {
+        path: String,
+        rule: RuleInfo,
+        /// `yes`, `no` or `quit`.
+        decision: &'static str,
+    },
+    /// In a dry-run this reports what would have been removed.
+    Cleaned {
+        path: String,
+        rule: RuleInfo,
+        size_bytes: u64,
+    },
+    Failed {
+        path: String,
+        rule: RuleInfo,
+        size_bytes: u64,
+        error: String,
+    },
+    Summary(RunSummary),
+    /// `putzen watch` found `path` below its free-space threshold and starts
+    /// a cleanup.
+    DiskPressure {
+        path: String,
+        free_bytes: u64,
+        below_bytes: u64,
+    },
+}
+
+impl Event {
+    pub fn new(dry_run: bool, kind: EventKind) -> Self {
+        Self {
+            timestamp: jiff::Timestamp::now().to_string(),
+            dry_run,
+            kind,
+        }
+    }
+}
+
+/// Format a file time as RFC 3339, e.g. `2024-05-01T12:00:00Z`.
+pub fn rfc3339(time: SystemTime) -> Option<String> {
+    jiff::Timestamp::try_from(time).ok().map(|t| t.to_string())
+}
+
+/// One entry of the final `--format json` document.
+#[derive(Debug, Serialize)]
+struct CandidateReport {
+    path: String,
+    rule: RuleInfo,
+    size_bytes: u64,
+    newest_mtime: Option<String>,
+    decision: Option<&'static str>,
+    /// `cleaned`, `would_clean` (dry-run), `failed` or `skipped`.
+    outcome: &'static str,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    dry_run: bool,
+    candidates: &'a [CandidateReport],
+    summary: &'a RunSummary,
+}
+
+/// Writes events to `out` in the requested format and forwards everything
+/// else to the wrapped observer (e.g. the highscore board).
+pub struct Reporter<W: Write> {
+    format: OutputFormat,
+    out: W,
+    candidates: Vec<CandidateReport>,
+    inner: Box<dyn RunObserver>,
+}
+
+impl<W: Write> Reporter<W> {
+    pub fn new(format: OutputFormat, out: W, inner: Box<dyn RunObserver>) -> Self {
+        Self {
+            format,
+            out,
+            candidates: Vec::new(),
+            inner,
+        }
+    }
+
+    pub fn into_writer(self) -> W {
+        self.out
+    }
+
+    fn candidate(&mut self, path: &str) -> Option<&mut CandidateReport> {
+        self.candidates.iter_mut().rev().find(|c| c.path == path)
+    }
+
+    fn collect(&mut self, event: &Event) {
+        match &event.kind {
+            EventKind::CandidateFound {
+                path,
+                rule,
+                size_bytes,
+                newest_mtime,
+            } => self.candidates.push(CandidateReport {
+                path: path.clone(),
+                rule: rule.clone(),
+                size_bytes: *size_bytes,
+                newest_mtime: newest_mtime.clone(),
+                decision: None,
+                outcome: "skipped",
+                error: None,
+            }),
+            EventKind::Decision { path, decision, .. } => {
+                if let Some(c) = self.candidate(path) {
+                    c.decision = Some(decision);
+                }
+            }
+            EventKind::Cleaned { path, .. } => {
+                let outcome = if event.dry_run {
+                    "would_clean"
+                } else {
+                    "cleaned"
+                };
+                if let Some(c) = self.candidate(path) {
+                    c.outcome = outcome;
+                }
+            }
+            EventKind::Failed { path, error, .. } => {
+                if let Some(c) = self.candidate(path) {
+                    c.outcome = "failed";
+                    c.error = Some(error.clone());
+                }
+            }
+            EventKind::Summary(summary) => {
+                let report = Report {
+                    dry_run: event.dry_run,
+                    candidates: &self.candidates,
+                    summary,
+                };
+                // A closed stdout (e.g. `| head`) must not abort a cleanup.
+                if serde_json::to_writer_pretty(&mut self.out, &report).is_ok() {
+                    let _ = writeln!(self.out);
+                }
+            }
+            EventKind::DiskPressure { .. } => {}
+        }
+    }
+}
+
+impl<W: Write> RunObserver for Reporter<W> {
+    fn on_folder_cleaned(&mut self, size: u64) -> Option<String> {
+        self.inner.on_folder_cleaned(size)
+    }
+
+    fn on_run_complete(&mut self, total: u64) -> Option<String> {
+        self.inner.on_run_complete(total)
+    }
+
+    fn on_event(&mut self, event: &Event) {
+        match self.format {
+            OutputFormat::Ndjson => {
+                if serde_json::to_writer(&mut self.out, event).is_ok() {
+                    let _ = writeln!(self.out);
+                }
+            }
+            OutputFormat::Json => self.collect(event),
+            OutputFormat::Text => {}
+        }
+        self.inner.on_event(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NoOpObserver;
+
+    fn rule() -> RuleInfo {
+        RuleInfo::from(&FileToFolderMatch::new("Cargo.toml", "target").with_ecosystem("rust"))
+    }
+
+    fn run(format: OutputFormat) -> String {
+        let mut reporter = Reporter::new(format, Vec::new(), Box::new(NoOpObserver));
+        let events = [
+            EventKind::CandidateFound {
+                path: "/src/foo/target".into(),
+                rule: rule(),
+                size_bytes: 42,
+                newest_mtime: Some("2024-05-01T12:00:00Z".into()),
+            },
+            EventKind::Decision {
+                path: "/src/foo/target".into(),
+                rule: rule(),
+                decision: "yes",
+            },
+            EventKind::Cleaned {
+                path: "/src/foo/target".into(),
+                rule: rule(),
+                size_bytes: 42,
+            },
+            EventKind::Summary(RunSummary {
+                found: 1,
+                cleaned: 1,
+                freed_bytes: 42,
+                ..Default::default()
+            }),
+        ];
+        for kind in events {
+            reporter.on_event(&Event::new(false, kind));
+        }
+        String::from_utf8(reporter.into_writer()).unwrap()
+    }
+
+    #[test]
+    fn format_parses_known_names_only() {
+        assert_eq!("json".parse(), Ok(OutputFormat::Json));
+        assert_eq!("ndjson".parse(), Ok(OutputFormat::Ndjson));
+        assert_eq!("text".parse(), Ok(OutputFormat::Text));
+        assert!("yaml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn ndjson_writes_one_tagged_event_per_line() {
+        let out = run(OutputFormat::Ndjson);
+        let lines: Vec<serde_json::Value> = out
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        let names: Vec<&str> = lines.iter().map(|v| v["event"].as_str().unwrap()).collect();
+        assert_eq!(names, ["candidate_found", "decision", "cleaned", "summary"]);
+        assert_eq!(lines[0]["rule"]["ecosystem"], "rust");
+        assert_eq!(lines[0]["size_bytes"], 42);
+        assert_eq!(lines[0]["dry_run"], false);
+        assert!(lines[0]["timestamp"].is_string());
+        assert_eq!(lines[3]["freed_bytes"], 42);
+    }
+
+    #[test]
+    fn json_prints_one_document_at_the_summary() {
+        let out = run(OutputFormat::Json);
+        let doc: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(doc["candidates"][0]["outcome"], "cleaned");
+        assert_eq!(doc["candidates"][0]["decision"], "yes");
+        assert_eq!(doc["summary"]["cleaned"], 1);
+        assert_eq!(doc["dry_run"], false);
+    }
+}
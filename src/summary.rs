@@ -0,0 +1,277 @@
+//! End-of-run summary of the main command: one row per ecosystem with
+//! folders found and cleaned, bytes freed and skipped, followed by the
+//! projects that gave back the most space.
+
+use crate::caches::format::tildify;
+use crate::report::{Event, EventKind};
+use crate::{HumanReadable, RunObserver};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// How many projects the summary lists.
+const TOP_PROJECTS: usize = 10;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EcosystemTotals {
+    pub found: usize,
+    /// Folders deleted, or that would have been in a dry-run.
+    pub cleaned: usize,
+    pub freed: usize,
+    pub skipped: usize,
+}
+
+/// Built from the events of a run, fed through [`RunTable::on_event`] or
+/// [`Recording`]. Events name the rule and its ecosystem, which a
+/// `FolderProcessed` result does not.
+#[derive(Debug, Default)]
+pub struct RunTable {
+    ecosystems: BTreeMap<&'static str, EcosystemTotals>,
+    /// Freed bytes per project root (the parent of the artifact folder).
+    projects: HashMap<PathBuf, usize>,
+}
+
+impl RunTable {
+    /// Tally one step of a run: every candidate counts as found and skipped
+    /// until its `Cleaned` event (also sent in a dry-run) moves it over.
+    pub fn on_event(&mut self, event: &Event) {
+        match &event.kind {
+            EventKind::CandidateFound {
+                rule, size_bytes, ..
+            } => {
+                let row = self.ecosystems.entry(rule.ecosystem).or_default();
+                row.found += 1;
+                row.skipped += *size_bytes as usize;
+            }
+            EventKind::Cleaned {
+                path,
+                rule,
+                size_bytes,
+            } => {
+                let size = *size_bytes as usize;
+                let row = self.ecosystems.entry(rule.ecosystem).or_default();
+                row.cleaned += 1;
+                row.freed += size;
+                row.skipped = row.skipped.saturating_sub(size);
+                let path = Path::new(path);
+                let project = path.parent().unwrap_or(path);
+                *self.projects.entry(project.to_path_buf()).or_default() += size;
+            }
+            _ => {}
+        }
+    }
+
+    /// Bytes freed over all ecosystems, or that would have been in a dry-run.
+    pub fn freed(&self) -> usize {
+        self.ecosystems.values().map(|row| row.freed).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ecosystems.is_empty()
+    }
+
+    pub fn ecosystem(&self, name: &str) -> Option<EcosystemTotals> {
+        self.ecosystems.get(name).copied()
+    }
+
+    /// Projects ordered by freed bytes, largest first; ties by path.
+    pub fn top_projects(&self, n: usize) -> Vec<(&Path, usize)> {
+        let mut projects: Vec<(&Path, usize)> = self
+            .projects
+            .iter()
+            .filter(|(_, freed)| **freed > 0)
+            .map(|(path, freed)| (path.as_path(), *freed))
+            .collect();
+        projects.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        projects.truncate(n);
+        projects
+    }
+
+    /// Render the table. A dry-run labels the columns "would clean" and
+    /// "would free" instead of "cleaned" and "freed".
+    pub fn render(&self, dry_run: bool, home: Option<&Path>) -> String {
+        let (cleaned, freed) = if dry_run {
+            ("would clean", "would free")
+        } else {
+            ("cleaned", "freed")
+        };
+        let mut out = format!(
+            "{:<12} {:>6} {:>11} {:>10} {:>10}\n",
+            "ecosystem", "found", cleaned, freed, "skipped"
+        );
+        let mut total = EcosystemTotals::default();
+        for (name, row) in &self.ecosystems {
+            out.push_str(&row_line(name, row));
+            total.found += row.found;
+            total.cleaned += row.cleaned;
+            total.freed += row.freed;
+            total.skipped += row.skipped;
+        }
+        if self.ecosystems.len() > 1 {
+            out.push_str(&row_line("total", &total));
+        }
+
+        let top = self.top_projects(TOP_PROJECTS);
+        if !top.is_empty() {
+            out.push_str(&format!("\ntop projects by {freed} space:\n"));
+            for (path, bytes) in top {
+                out.push_str(&format!(
+                    "{:>10}  {}\n",
+                    bytes.as_human_readable(),
+                    tildify(path, home)
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// Passes everything on to `inner` and feeds the events to `table` on the
+/// way, so the table sees the same sizes the report does.
+pub struct Recording<'a> {
+    pub table: &'a mut RunTable,
+    pub inner: &'a mut dyn RunObserver,
+}
+
+impl RunObserver for Recording<'_> {
+    fn on_folder_cleaned(&mut self, size: u64) -> Option<String> {
+        self.inner.on_folder_cleaned(size)
+    }
+
+    fn on_run_complete(&mut self, total: u64) -> Option<String> {
+        self.inner.on_run_complete(total)
+    }
+
+    fn on_event(&mut self, event: &Event) {
+        self.table.on_event(event);
+        self.inner.on_event(event);
+    }
+}
+
+fn row_line(name: &str, row: &EcosystemTotals) -> String {
+    format!(
+        "{:<12} {:>6} {:>11} {:>10} {:>10}\n",
+        name,
+        row.found,
+        row.cleaned,
+        row.freed.as_human_readable(),
+        row.skipped.as_human_readable()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::RuleInfo;
+    use crate::FileToFolderMatch;
+
+    static RUST: FileToFolderMatch =
+        FileToFolderMatch::new("Cargo.toml", "target").with_ecosystem("rust");
+    static JS: FileToFolderMatch =
+        FileToFolderMatch::new("package.json", "node_modules").with_ecosystem("javascript");
+
+    /// Feed `table` the events of one candidate, cleaned or not.
+    fn run(table: &mut RunTable, rule: &FileToFolderMatch, path: &str, size: u64, cleaned: bool) {
+        let rule = RuleInfo::from(rule);
+        table.on_event(&Event::new(
+            false,
+            EventKind::CandidateFound {
+                path: path.into(),
+                rule: rule.clone(),
+                size_bytes: size,
+                newest_mtime: None,
+            },
+        ));
+        if cleaned {
+            table.on_event(&Event::new(
+                false,
+                EventKind::Cleaned {
+                    path: path.into(),
+                    rule,
+                    size_bytes: size,
+                },
+            ));
+        }
+    }
+
+    #[test]
+    fn groups_results_by_ecosystem() {
+        let mut table = RunTable::default();
+        run(&mut table, &RUST, "/src/a/target", 300, true);
+        run(&mut table, &RUST, "/src/b/target", 50, false);
+
+        assert_eq!(
+            table.ecosystem("rust"),
+            Some(EcosystemTotals {
+                found: 2,
+                cleaned: 1,
+                freed: 300,
+                skipped: 50
+            })
+        );
+        assert_eq!(table.ecosystem("javascript"), None);
+        assert_eq!(table.freed(), 300);
+    }
+
+    #[test]
+    fn top_projects_are_ranked_by_freed_bytes() {
+        let mut table = RunTable::default();
+        run(&mut table, &RUST, "/src/small/target", 10, true);
+        run(&mut table, &RUST, "/src/big/target", 900, true);
+        run(&mut table, &JS, "/src/big/node_modules", 100, true);
+        run(&mut table, &RUST, "/src/kept/target", 5000, false);
+
+        let top = table.top_projects(10);
+        let names: Vec<_> = top
+            .iter()
+            .map(|(p, bytes)| (p.file_name().unwrap().to_str().unwrap(), *bytes))
+            .collect();
+        assert_eq!(names, [("big", 1000), ("small", 10)]);
+    }
+
+    #[test]
+    fn dry_run_labels_columns_as_would_free() {
+        let mut table = RunTable::default();
+        run(&mut table, &RUST, "/src/a/target", 2048, true);
+
+        let text = table.render(true, None);
+        assert!(text.contains("would free"), "got:\n{text}");
+        assert!(text.contains("2.0KiB"), "got:\n{text}");
+        assert!(
+            text.contains("top projects by would free space"),
+            "got:\n{text}"
+        );
+        assert!(!table.render(false, None).contains("would"));
+    }
+
+    #[test]
+    fn recording_forwards_to_the_inner_observer() {
+        struct Counting(usize);
+        impl RunObserver for Counting {
+            fn on_folder_cleaned(&mut self, _: u64) -> Option<String> {
+                None
+            }
+            fn on_run_complete(&mut self, _: u64) -> Option<String> {
+                None
+            }
+            fn on_event(&mut self, _: &Event) {
+                self.0 += 1;
+            }
+        }
+        let mut table = RunTable::default();
+        let mut inner = Counting(0);
+        let mut recording = Recording {
+            table: &mut table,
+            inner: &mut inner,
+        };
+        recording.on_event(&Event::new(
+            false,
+            EventKind::Cleaned {
+                path: "/src/a/target".into(),
+                rule: RuleInfo::from(&RUST),
+                size_bytes: 7,
+            },
+        ));
+        assert_eq!(inner.0, 1);
+        assert_eq!(table.freed(), 7);
+    }
+}
@@ -0,0 +1,38 @@
+//! Integration test: `putzen caches --delete-older-than … --json` keeps
+//! stdout machine-readable, even on the very first run.
+
+use std::fs;
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+#[test]
+fn delete_older_than_json_parses_on_a_fresh_highscore_board() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path().join("home");
+    let config = tmp.path().join("config");
+    let old = home.join("seed/stale");
+    fs::create_dir_all(&old).unwrap();
+    fs::create_dir_all(&config).unwrap();
+    let blob = old.join("blob");
+    fs::write(&blob, [0u8; 1024]).unwrap();
+    let long_ago = SystemTime::now() - Duration::from_secs(100 * 86_400);
+    filetime::set_file_mtime(&blob, filetime::FileTime::from_system_time(long_ago)).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_putzen"))
+        .args(["caches", "--root"])
+        .arg(home.join("seed"))
+        .args(["--delete-older-than", "30d", "-y", "--json"])
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", &config)
+        .env("PUTZEN_CONFIG", config.join("putzen/config.toml"))
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap_or_else(|e| {
+        panic!("stdout is not JSON ({e}):\n{stdout}");
+    });
+    assert_eq!(json["deleted"].as_array().unwrap().len(), 1);
+    assert!(!old.exists());
+}